@@ -0,0 +1,117 @@
+use crate::*;
+
+#[error_code]
+pub enum GrizzlyError {
+    #[msg("Merchant has not registered a voucher signer")]
+    VoucherSignerNotSet,
+    #[msg("Voucher must be preceded by a single Ed25519 signature verification instruction")]
+    InvalidVoucherSignatureInstruction,
+    #[msg("Voucher was not signed by the merchant's voucher signer")]
+    VoucherSignerMismatch,
+    #[msg("Signed voucher payload does not match instruction arguments")]
+    VoucherPayloadMismatch,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Minting would exceed the merchant's max reward points supply")]
+    MaxPointsSupplyExceeded,
+    #[msg("New max reward points supply must be greater than the current max")]
+    MaxPointsSupplyNotIncreased,
+    #[msg("Too many streak tiers")]
+    TooManyStreakTiers,
+    #[msg("Streak tiers must have increasing min streak days and a multiplier of at least 1x")]
+    InvalidStreakTiers,
+    #[msg("Merchant has not enabled cash back redemption")]
+    CashBackDisabled,
+    #[msg("Reward points redeemed are below the merchant's minimum redemption")]
+    BelowMinRedemption,
+    #[msg("Points vault balance is insufficient for cash back")]
+    InsufficientPointsVault,
+    #[msg("Merchant belongs to a coalition, coalition accounts are required")]
+    CoalitionAccountsRequired,
+    #[msg("Merchant already belongs to a coalition")]
+    AlreadyInCoalition,
+    #[msg("Merchant does not accept these reward points")]
+    ExchangeRateDisabled,
+    #[msg("Swap would exceed the exchange rate's daily volume cap")]
+    ExchangeVolumeCapExceeded,
+    #[msg("Stake lock duration must be greater than zero")]
+    InvalidStakeDuration,
+    #[msg("Customer has no staked reward points")]
+    NothingStaked,
+    #[msg("Customer has no pending reward points")]
+    NoPendingPoints,
+    #[msg("Pending reward points have not vested yet")]
+    PointsNotVested,
+    #[msg("Vesting period must not be negative")]
+    InvalidVestingPeriod,
+    #[msg("Too many loyalty levels")]
+    TooManyLoyaltyLevels,
+    #[msg("Loyalty level uri must be set and within max length")]
+    InvalidLoyaltyLevels,
+    #[msg("Loyalty NFT already at max level")]
+    MaxLoyaltyLevelReached,
+    #[msg("Loyalty NFT has not been burned")]
+    LoyaltyNftNotBurned,
+    #[msg("Invalid loyalty NFT version")]
+    InvalidLoyaltyNftVersion,
+    #[msg("Only merchant authority or NFT owner can unlock loyalty NFT")]
+    UnauthorizedLoyaltyNftUnlock,
+    #[msg("Loyalty NFT is not locked")]
+    LoyaltyNftNotLocked,
+    #[msg("Membership has been revoked")]
+    RevokedMembership,
+    #[msg("Loyalty NFT does not match membership")]
+    InvalidMembership,
+    #[msg("Loyalty collection has reached max members")]
+    MaxMembersReached,
+    #[msg("Payment accounts required for member mint price")]
+    MintPriceAccountsRequired,
+    #[msg("Member template base uri, name prefix or symbol too long")]
+    InvalidMemberTemplate,
+    #[msg("Membership has expired")]
+    MembershipExpired,
+    #[msg("Membership does not expire")]
+    MembershipDoesNotExpire,
+    #[msg("Membership duration must not be negative")]
+    InvalidMembershipDuration,
+    #[msg("Perk uses must be 0 (no perks) or at least 2")]
+    InvalidPerkUses,
+    #[msg("Member master accounts required to mint a print edition member nft")]
+    MemberMasterAccountsRequired,
+    #[msg("Collection creator shares must add up to 100, with at most 4 creators")]
+    InvalidCreatorShares,
+    #[msg("Reward points mint already initialized")]
+    RewardPointsAlreadyInitialized,
+    #[msg("Loyalty collection already initialized")]
+    LoyaltyCollectionAlreadyInitialized,
+    #[msg("Basis points must not exceed 10000")]
+    InvalidBasisPoints,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Merchant account does not belong to authority")]
+    InvalidMerchantAccount,
+    #[msg("Points mint decimals must not exceed 9")]
+    InvalidPointsDecimals,
+    #[msg("New exchange rate must be greater than 0")]
+    InvalidExchangeRate,
+    #[msg("Swap amount converts to 0 destination reward points")]
+    SwapAmountTooSmall,
+    #[msg("Staked reward points below merchant's min stake amount")]
+    StakeBelowMinimum,
+    #[msg("Not enough pending reward points to cancel")]
+    InsufficientPendingPoints,
+    #[msg("Points vesting is not available to coalition merchants")]
+    VestingUnavailableForCoalition,
+    #[msg("Print edition member nft metadata is immutable")]
+    PrintEditionMetadataImmutable,
+    #[msg("Print edition member nfts can't be reissued")]
+    PrintEditionReissueUnsupported,
+    #[msg("Loyalty levels can't be removed")]
+    LoyaltyLevelsRemoved,
+    #[msg("Merchant account can't be a collection creator")]
+    MerchantCollectionCreator,
+    #[msg("Programmable member nfts can't be soulbound, print editions or have perks")]
+    ProgrammableLoyaltyNftIncompatible,
+    #[msg("Programmable member nft accounts required")]
+    ProgrammableLoyaltyNftAccountsRequired,
+}
@@ -0,0 +1,105 @@
+use crate::*;
+
+// emitted when reward points supply crosses one of POINTS_SUPPLY_WARNING_THRESHOLDS
+#[event]
+pub struct PointsSupplyWarning {
+    pub merchant: Pubkey,
+    pub supply: u64,
+    pub max_points_supply: u64,
+    pub threshold_basis_points: u16,
+}
+
+// emitted at checkout, streak bonus and stake boost reported separately from base reward points
+// amount is the price before the loyalty discount
+#[event]
+pub struct TransactionRewarded {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub amount: u64,
+    pub loyalty_discount: u64,
+    pub base_points: u64,
+    pub streak_bonus_points: u64,
+    pub current_streak: u16,
+    pub stake_boost_points: u64,
+    pub points_pending: bool,
+}
+
+// emitted when customer redeems reward points for "usdc" cash back
+#[event]
+pub struct PointsRedeemedForCash {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub points: u64,
+    pub payout: u64,
+}
+
+// emitted when customer redeems coalition points at a member merchant
+#[event]
+pub struct CoalitionPointsRedeemed {
+    pub coalition: Pubkey,
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub points: u64,
+}
+
+// emitted when customer swaps one merchant's reward points for another's
+#[event]
+pub struct PointsSwapped {
+    pub source_merchant: Pubkey,
+    pub destination_merchant: Pubkey,
+    pub customer: Pubkey,
+    pub source_amount: u64,
+    pub destination_amount: u64,
+}
+
+// emitted when customer unstakes reward points, penalty burned if unstaked before unlock
+#[event]
+pub struct PointsUnstaked {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+}
+
+// emitted when pending reward points are minted to customer after vesting period
+#[event]
+pub struct PointsVested {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub points: u64,
+}
+
+// emitted when merchant cancels pending reward points, e.g. for a refunded checkout
+#[event]
+pub struct PendingPointsCancelled {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub points: u64,
+}
+
+// emitted when customer burns reward points to upgrade loyalty NFT to the next level
+#[event]
+pub struct LoyaltyNftUpgraded {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub nft_mint: Pubkey,
+    pub level: u8,
+    pub points: u64,
+}
+
+// emitted when merchant revokes a member's loyalty NFT
+#[event]
+pub struct MembershipRevoked {
+    pub merchant: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+// emitted when merchant redeems member nft perks (e.g. free coffee)
+#[event]
+pub struct PerkRedeemed {
+    pub merchant: Pubkey,
+    pub customer: Pubkey,
+    pub nft_mint: Pubkey,
+    pub uses: u64,
+    pub perks_redeemed: u64,
+}
@@ -0,0 +1,64 @@
+// admit merchant to coalition, requires both coalition authority and merchant authority to sign
+use crate::*;
+
+#[derive(Accounts)]
+pub struct AdmitCoalitionMerchant<'info> {
+    // coalition authority approving admission
+    pub coalition_authority: Signer<'info>,
+
+    // authority of merchant account joining coalition
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // coalition account
+    #[account(
+        mut,
+        seeds = [COALITION_SEED.as_bytes(), coalition_authority.key().as_ref()],
+        bump,
+        constraint = coalition.authority == coalition_authority.key()
+    )]
+    pub coalition: Account<'info, Coalition>,
+
+    // initialize merchant's coalition ledger
+    #[account(
+        init,
+        seeds = [COALITION_MEMBER_SEED.as_bytes(), coalition.key().as_ref(), merchant.key().as_ref()],
+        bump,
+        payer = authority,
+        space = CoalitionMember::LEN
+    )]
+    pub coalition_member: Account<'info, CoalitionMember>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn admit_coalition_merchant_handler(ctx: Context<AdmitCoalitionMerchant>) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.merchant.coalition,
+        Pubkey::default(),
+        GrizzlyError::AlreadyInCoalition
+    );
+    // coalition points don't vest, merchant must turn off points vesting before joining
+    require!(
+        ctx.accounts.merchant.points_vesting_period == 0,
+        GrizzlyError::VestingUnavailableForCoalition
+    );
+
+    // merchant now mints coalition points at checkout
+    ctx.accounts.merchant.coalition = ctx.accounts.coalition.key();
+    ctx.accounts.coalition.member_count =
+        ctx.accounts.coalition.member_count.checked_add(1).unwrap();
+
+    ctx.accounts.coalition_member.coalition = ctx.accounts.coalition.key();
+    ctx.accounts.coalition_member.merchant = ctx.accounts.merchant.key();
+    Ok(())
+}
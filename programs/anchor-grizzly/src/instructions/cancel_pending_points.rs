@@ -0,0 +1,48 @@
+// cancel customer's pending reward points for a refunded or charged back checkout, signed by merchant authority
+// most recent pending reward points are cancelled first
+use crate::*;
+
+#[derive(Accounts)]
+pub struct CancelPendingPoints<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // customer whose pending reward points are cancelled
+    pub customer: SystemAccount<'info>,
+
+    // merchant account, releases cancelled points from pending reward points supply
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // customer account tracking pending reward points
+    #[account(
+        mut,
+        seeds = [CUSTOMER_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+    )]
+    pub customer_state: Box<Account<'info, CustomerState>>,
+}
+
+pub fn cancel_pending_points_handler(ctx: Context<CancelPendingPoints>, points: u64) -> Result<()> {
+    ctx.accounts.customer_state.cancel_pending_points(points)?;
+    ctx.accounts.merchant.pending_points_supply = ctx
+        .accounts
+        .merchant
+        .pending_points_supply
+        .checked_sub(points)
+        .unwrap();
+
+    emit!(PendingPointsCancelled {
+        merchant: ctx.accounts.merchant.key(),
+        customer: ctx.accounts.customer.key(),
+        points,
+    });
+
+    Ok(())
+}
@@ -13,21 +13,19 @@ pub struct CreateCollectionNft<'info> {
         mut,
         seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
         bump,
-        constraint = merchant.authority == authority.key()
+        constraint = merchant.authority == authority.key(),
+        constraint = !merchant.loyalty_collection_initialized @ GrizzlyError::LoyaltyCollectionAlreadyInitialized
     )]
     pub merchant: Account<'info, MerchantState>,
 
-    // create mint for "collection" nft for merchant loyalty NFT program
+    /// CHECK: mint for "collection" nft for merchant loyalty NFT program, created in handler
+    /// anchor init would run before the merchant's init flag constraint and fail on account creation
     #[account(
-        init_if_needed,
+        mut,
         seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
         bump,
-        payer = authority,
-        mint::decimals = 0,
-        mint::authority = loyalty_collection_mint,
-        mint::freeze_authority = loyalty_collection_mint
     )]
-    pub loyalty_collection_mint: Account<'info, Mint>,
+    pub loyalty_collection_mint: UncheckedAccount<'info>,
 
     // create metadata account for reward points mint
     /// CHECK: initialize metadata account for reward points mint via CPI to token-metadata program
@@ -45,18 +43,17 @@ pub struct CreateCollectionNft<'info> {
     )]
     pub master_edition: UncheckedAccount<'info>,
 
-    // token account for collection nft
+    /// CHECK: collection nft vault owned by merchant account, moved out with withdraw_collection_nft
+    /// created in handler once the collection mint exists
     #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = loyalty_collection_mint,
-        associated_token::authority = authority
+        mut,
+        seeds = [COLLECTION_VAULT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub collection_vault: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_metadata_program: Program<'info, Metadata>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -67,7 +64,21 @@ pub fn create_collection_nft_handler(
     uri: String,
     name: String,
     symbol: String,
+    seller_fee_basis_points: u16,
+    creators: Vec<CollectionCreator>,
 ) -> Result<()> {
+    // secondary sale royalty and creator split, inherited by member nfts
+    validate_royalties(
+        seller_fee_basis_points,
+        &creators,
+        ctx.accounts.merchant.key(),
+    )?;
+    let authority_is_creator = creators
+        .iter()
+        .any(|creator| creator.address == ctx.accounts.authority.key());
+
+    // mark loyalty collection initialized, see merchant lifecycle in state.rs
+    ctx.accounts.merchant.loyalty_collection_initialized = true;
     // update merchant account with loyalty collection mint and discount basis points
     ctx.accounts.merchant.loyalty_collection_mint = ctx.accounts.loyalty_collection_mint.key();
     ctx.accounts.merchant.loyalty_discount_basis_points = loyalty_discount_basis_points;
@@ -79,13 +90,46 @@ pub fn create_collection_nft_handler(
         merchant.as_ref(),
         &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
     ]];
+    let vault_signer_seeds: &[&[&[u8]]] = &[&[
+        COLLECTION_VAULT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("collection_vault").unwrap()],
+    ]];
 
-    // mint 1 collection nft to token account
+    // create collection nft mint, PDA is mint and freeze authority
+    create_pda_mint(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.loyalty_collection_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        0,
+        signer_seeds,
+    )?;
+
+    // create collection vault token account owned by merchant account
+    create_pda_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.collection_vault.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        TokenAccount::LEN,
+        &ctx.accounts.token_program.key(),
+        vault_signer_seeds,
+    )?;
+    initialize_account3(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        InitializeAccount3 {
+            account: ctx.accounts.collection_vault.to_account_info(),
+            mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            authority: ctx.accounts.merchant.to_account_info(),
+        },
+    ))?;
+
+    // mint 1 collection nft to collection vault
     let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         MintTo {
             mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
-            to: ctx.accounts.token_account.to_account_info(),
+            to: ctx.accounts.collection_vault.to_account_info(),
             authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
         },
         signer_seeds,
@@ -111,12 +155,17 @@ pub fn create_collection_nft_handler(
             name: name,
             symbol: symbol,
             uri: uri,
-            seller_fee_basis_points: 0,
-            creators: Some(vec![Creator {
-                address: ctx.accounts.authority.key(),
-                verified: false,
-                share: 100,
-            }]),
+            seller_fee_basis_points,
+            creators: Some(
+                creators
+                    .iter()
+                    .map(|creator| Creator {
+                        address: creator.address,
+                        verified: false,
+                        share: creator.share,
+                    })
+                    .collect(),
+            ),
             collection: None,
             uses: None,
         },
@@ -145,14 +194,16 @@ pub fn create_collection_nft_handler(
         Some(0),
     )?;
 
-    // sign metadata to verify creator
-    sign_metadata(CpiContext::new(
-        ctx.accounts.token_metadata_program.to_account_info(),
-        SignMetadata {
-            creator: ctx.accounts.authority.to_account_info(),
-            metadata: ctx.accounts.metadata_account.to_account_info(),
-        },
-    ))?;
+    // sign metadata to verify merchant authority as creator
+    if authority_is_creator {
+        sign_metadata(CpiContext::new(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            SignMetadata {
+                creator: ctx.accounts.authority.to_account_info(),
+                metadata: ctx.accounts.metadata_account.to_account_info(),
+            },
+        ))?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,172 @@
+// create merchant's member master nft, printed as numbered member nfts in print edition membership mode
+// edition metadata is copied from the master and immutable, so level upgrades and metadata refresh
+// do not apply to print edition member nfts
+// note this instruction requires requesting extra compute units
+use crate::*;
+
+#[derive(Accounts)]
+pub struct CreateMemberMaster<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program, PDA holds the master nft
+    #[account(
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    /// CHECK: collection metadata account, member nfts inherit its royalty and creators
+    #[account(
+        address=find_metadata_account(&loyalty_collection_mint.key()).0
+    )]
+    pub collection_metadata_account: UncheckedAccount<'info>,
+
+    // merchant's member nft metadata template
+    #[account(
+        seeds = [MEMBER_TEMPLATE_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub member_template: Account<'info, MemberTemplate>,
+
+    // mint for member master nft
+    #[account(
+        init,
+        seeds = [MEMBER_MASTER_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = loyalty_collection_mint,
+        mint::freeze_authority = loyalty_collection_mint
+    )]
+    pub member_master_mint: Account<'info, Mint>,
+
+    /// CHECK: initialize metadata account for member master mint via CPI to token-metadata program
+    #[account(
+        mut,
+        address=find_metadata_account(&member_master_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: master edition account
+    #[account(
+        mut,
+        address=find_master_edition_account(&member_master_mint.key()).0
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    // token account for member master nft, owned by loyalty collection mint PDA
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = member_master_mint,
+        associated_token::authority = loyalty_collection_mint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_member_master_handler(ctx: Context<CreateMemberMaster>) -> Result<()> {
+    // PDA for signing
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+    let authority = ctx.accounts.authority.key();
+    let merchant_signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
+
+    // member nfts inherit the collection's royalty and creators
+    let (seller_fee_basis_points, creators) =
+        member_royalties(&ctx.accounts.collection_metadata_account, merchant)?;
+
+    // mint 1 master nft to token account
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.member_master_mint.to_account_info(),
+            to: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    mint_to(cpi_ctx, 1)?;
+
+    // editions copy the master's metadata and perk uses, the master is not a collection member
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata_account.to_account_info(),
+                mint: ctx.accounts.member_master_mint.to_account_info(),
+                mint_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
+                update_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        DataV2 {
+            seller_fee_basis_points,
+            creators,
+            uses: ctx.accounts.merchant.member_perks(0),
+            ..ctx.accounts.member_template.edition_data()
+        },
+        true,
+        true,
+        None,
+    )?;
+
+    // verify merchant account as creator, copied to print editions
+    sign_metadata(CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        SignMetadata {
+            creator: ctx.accounts.merchant.to_account_info(),
+            metadata: ctx.accounts.metadata_account.to_account_info(),
+        },
+        merchant_signer_seeds,
+    ))?;
+
+    // unlimited supply, member count is capped by the merchant's max members
+    create_master_edition_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMasterEditionV3 {
+                payer: ctx.accounts.authority.to_account_info(),
+                mint: ctx.accounts.member_master_mint.to_account_info(),
+                edition: ctx.accounts.master_edition.to_account_info(),
+                mint_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
+                update_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
+                metadata: ctx.accounts.metadata_account.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+    )?;
+
+    Ok(())
+}
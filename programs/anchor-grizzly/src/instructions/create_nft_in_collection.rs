@@ -1,4 +1,7 @@
 // create nft in loyalty NFT collection
+// minted as a numbered print edition of the merchant's member master nft if print edition membership is on
+// capped by the merchant's max members, customer pays the member mint price if one is set
+// minted as a programmable nft with the merchant's rule set if programmable member nfts are on
 // note this instruction requires requesting extra compute units
 use crate::*;
 
@@ -45,8 +48,7 @@ pub struct CreateNftInCollection<'info> {
     pub collection_master_edition: UncheckedAccount<'info>,
 
     // mint address for customer loyalty nft
-    // note, this seems to break if customer burns their nft and tries to mint again
-    // may be due to metadata/master edition account not being closed when burned
+    // mint authority moves to the master edition, so a burned nft is replaced via reissue_loyalty_nft
     #[account(
         init_if_needed,
         seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
@@ -82,6 +84,96 @@ pub struct CreateNftInCollection<'info> {
     )]
     pub token_account: Account<'info, TokenAccount>,
 
+    /// CHECK: use authority record approving merchant account to redeem member nft perks
+    #[account(
+        mut,
+        address=find_use_authority_account(&customer_nft_mint.key(), &merchant.key()).0
+    )]
+    pub use_authority_record: UncheckedAccount<'info>,
+
+    /// CHECK: token metadata program burner PDA, required by approve_use_authority
+    #[account(
+        address=find_program_as_burner_account().0
+    )]
+    pub burner: UncheckedAccount<'info>,
+
+    // membership account for loyalty nft
+    #[account(
+        init,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), customer_nft_mint.key().as_ref()],
+        bump,
+        payer = customer,
+        space = Membership::LEN
+    )]
+    pub membership: Box<Account<'info, Membership>>,
+
+    // merchant's member nft metadata template
+    #[account(
+        mut,
+        seeds = [MEMBER_TEMPLATE_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub member_template: Box<Account<'info, MemberTemplate>>,
+
+    // merchant's payment destination, required if merchant set a member mint price
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        address = merchant.payment_destination,
+    )]
+    pub payment_destination: Option<Box<Account<'info, TokenAccount>>>,
+
+    // customer's "usdc" token account, required if merchant set a member mint price
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        constraint = customer_usdc_token_account.owner == customer.key()
+    )]
+    pub customer_usdc_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    // merchant's member master nft mint, required for print edition membership
+    #[account(
+        seeds = [MEMBER_MASTER_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub member_master_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// CHECK: member master metadata account, checked against member master mint
+    pub member_master_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: member master edition account, checked against member master mint
+    #[account(mut)]
+    pub member_master_edition: Option<UncheckedAccount<'info>>,
+
+    // loyalty collection mint PDA's token account holding the member master nft
+    #[account(
+        constraint = member_master_token_account.owner == loyalty_collection_mint.key()
+    )]
+    pub member_master_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: edition marker PDA for the member number, checked by token metadata program
+    #[account(mut)]
+    pub edition_marker: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token record PDA of the customer's token account, required for programmable member nfts
+    #[account(
+        mut,
+        address=find_token_record_account(&customer_nft_mint.key(), &token_account.key()).0
+    )]
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar, required for programmable member nfts
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, required if the merchant set a rule set
+    #[account(address = TOKEN_AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: merchant's rule set for programmable member nfts, checked by token metadata program
+    #[account(address = merchant.loyalty_nft_rule_set)]
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -89,12 +181,7 @@ pub struct CreateNftInCollection<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn create_nft_in_collection_handler(
-    ctx: Context<CreateNftInCollection>,
-    uri: String,
-    name: String,
-    symbol: String,
-) -> Result<()> {
+pub fn create_nft_in_collection_handler(ctx: Context<CreateNftInCollection>) -> Result<()> {
     // PDA for signing
     let merchant = ctx.accounts.merchant.key();
     let signer_seeds: &[&[&[u8]]] = &[&[
@@ -102,85 +189,143 @@ pub fn create_nft_in_collection_handler(
         merchant.as_ref(),
         &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
     ]];
+    let authority = ctx.accounts.authority.key();
+    let merchant_signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
 
-    // mint 1 nft to customer token account
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        MintTo {
-            mint: ctx.accounts.customer_nft_mint.to_account_info(),
-            to: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-        },
-        signer_seeds,
-    );
-    mint_to(cpi_ctx, 1)?;
-
-    // create metadata account
-    create_metadata_accounts_v3(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                metadata: ctx.accounts.metadata_account.to_account_info(),
-                mint: ctx.accounts.customer_nft_mint.to_account_info(),
-                mint_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                update_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                payer: ctx.accounts.customer.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
-            },
-            &signer_seeds,
-        ),
-        DataV2 {
-            name: name,
-            symbol: symbol,
-            uri: uri,
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        },
-        true,
-        true,
-        None,
-    )?;
+    // check collection size against merchant's max members
+    ctx.accounts
+        .merchant
+        .check_member_cap(loyalty_collection_size(
+            &ctx.accounts.collection_metadata_account,
+        )?)?;
 
-    // create master edition account
-    create_master_edition_v3(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMasterEditionV3 {
-                payer: ctx.accounts.customer.to_account_info(),
-                mint: ctx.accounts.customer_nft_mint.to_account_info(),
-                edition: ctx.accounts.master_edition.to_account_info(),
-                mint_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                update_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                metadata: ctx.accounts.metadata_account.to_account_info(),
-                token_program: ctx.accounts.token_program.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
-            },
-            &signer_seeds,
-        ),
-        Some(0),
-    )?;
+    // customer pays member mint price to merchant
+    let member_mint_price = ctx.accounts.merchant.member_mint_price;
+    if member_mint_price > 0 {
+        let payment_destination = ctx
+            .accounts
+            .payment_destination
+            .as_ref()
+            .ok_or(GrizzlyError::MintPriceAccountsRequired)?;
+        let customer_usdc_token_account = ctx
+            .accounts
+            .customer_usdc_token_account
+            .as_ref()
+            .ok_or(GrizzlyError::MintPriceAccountsRequired)?;
 
-    // verify nft as part of collection
-    set_and_verify_sized_collection_item(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            SetAndVerifySizedCollectionItem {
-                metadata: ctx.accounts.metadata_account.to_account_info(),
-                collection_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                payer: ctx.accounts.customer.to_account_info(),
-                update_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                collection_mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
-                collection_metadata: ctx.accounts.collection_metadata_account.to_account_info(),
-                collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+        msg!("Transfer Tokens");
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: customer_usdc_token_account.to_account_info(),
+                authority: ctx.accounts.customer.to_account_info(),
+                to: payment_destination.to_account_info(),
             },
-            &signer_seeds,
-        ),
-        None,
+        );
+        transfer(cpi_ctx, member_mint_price)?;
+    }
+
+    // sequential member number
+    let member_template = &mut ctx.accounts.member_template;
+    member_template.member_count = member_template.member_count.checked_add(1).unwrap();
+    let member_number = member_template.member_count;
+
+    let membership = &mut ctx.accounts.membership;
+    membership.merchant = merchant;
+    membership.nft_mint = ctx.accounts.customer_nft_mint.key();
+    membership.member_number = member_number;
+    membership.print_edition = ctx.accounts.merchant.print_edition_membership;
+    membership.expiry = ctx
+        .accounts
+        .merchant
+        .membership_expiry(Clock::get()?.unix_timestamp)?;
+
+    // print edition number is the member number
+    let print_edition = if ctx.accounts.merchant.print_edition_membership {
+        let (master_mint, master_metadata, master_edition, master_token_account, edition_marker) =
+            match (
+                ctx.accounts.member_master_mint.as_ref(),
+                ctx.accounts.member_master_metadata.as_ref(),
+                ctx.accounts.member_master_edition.as_ref(),
+                ctx.accounts.member_master_token_account.as_ref(),
+                ctx.accounts.edition_marker.as_ref(),
+            ) {
+                (Some(a), Some(b), Some(c), Some(d), Some(e)) => (a, b, c, d, e),
+                _ => return err!(GrizzlyError::MemberMasterAccountsRequired),
+            };
+        require_keys_eq!(
+            master_metadata.key(),
+            find_metadata_account(&master_mint.key()).0,
+            GrizzlyError::MemberMasterAccountsRequired
+        );
+        require_keys_eq!(
+            master_edition.key(),
+            find_master_edition_account(&master_mint.key()).0,
+            GrizzlyError::MemberMasterAccountsRequired
+        );
+        require_keys_eq!(
+            master_token_account.mint,
+            master_mint.key(),
+            GrizzlyError::MemberMasterAccountsRequired
+        );
+        Some(PrintEdition {
+            master_mint: master_mint.to_account_info(),
+            master_metadata: master_metadata.to_account_info(),
+            master_edition: master_edition.to_account_info(),
+            master_token_account: master_token_account.to_account_info(),
+            edition_marker: edition_marker.to_account_info(),
+            edition: member_number,
+        })
+    } else {
+        None
+    };
+
+    // member nfts inherit the collection's royalty and creators
+    let (seller_fee_basis_points, creators) = member_royalties(
+        &ctx.accounts.collection_metadata_account,
+        ctx.accounts.merchant.key(),
     )?;
 
-    Ok(())
+    mint_loyalty_nft(
+        MintLoyaltyNft {
+            payer: ctx.accounts.customer.to_account_info(),
+            owner: ctx.accounts.customer.to_account_info(),
+            loyalty_collection_mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            collection_metadata_account: ctx.accounts.collection_metadata_account.to_account_info(),
+            collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+            nft_mint: ctx.accounts.customer_nft_mint.to_account_info(),
+            metadata_account: ctx.accounts.metadata_account.to_account_info(),
+            master_edition: ctx.accounts.master_edition.to_account_info(),
+            token_account: ctx.accounts.token_account.to_account_info(),
+            merchant: ctx.accounts.merchant.to_account_info(),
+            use_authority_record: ctx.accounts.use_authority_record.to_account_info(),
+            burner: ctx.accounts.burner.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            print_edition,
+            programmable: programmable_nft(
+                &ctx.accounts.merchant,
+                ctx.accounts.token_record.as_ref(),
+                ctx.accounts.sysvar_instructions.as_ref(),
+                &ctx.accounts.associated_token_program.to_account_info(),
+                ctx.accounts.authorization_rules_program.as_ref(),
+                ctx.accounts.authorization_rules.as_ref(),
+            )?,
+        },
+        DataV2 {
+            seller_fee_basis_points,
+            creators,
+            uses: ctx.accounts.merchant.member_perks(0),
+            ..ctx.accounts.member_template.member_data(member_number)
+        },
+        ctx.accounts.merchant.soulbound_loyalty_nft,
+        signer_seeds,
+        merchant_signer_seeds,
+    )
 }
@@ -0,0 +1,61 @@
+// fund merchant's points vault with "usdc", backs reward points redeemed for cash back
+use crate::*;
+
+#[derive(Accounts)]
+pub struct FundPointsVault<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // "usdc" mint account
+    #[account(
+        address = USDC_MINT_PLACEHOLDER
+    )]
+    pub usdc_mint_placeholder: Account<'info, Mint>,
+
+    // merchant's payment destination, source of vault funds
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        address = merchant.payment_destination,
+    )]
+    pub payment_destination: Account<'info, TokenAccount>,
+
+    // init "usdc" points vault owned by merchant account if one does not exist
+    #[account(
+        init_if_needed,
+        seeds = [POINTS_VAULT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = usdc_mint_placeholder,
+        token::authority = merchant
+    )]
+    pub points_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn fund_points_vault_handler(ctx: Context<FundPointsVault>, amount: u64) -> Result<()> {
+    // transfer "usdc" from merchant's payment destination to points vault
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.payment_destination.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.points_vault.to_account_info(),
+        },
+    );
+    transfer(cpi_ctx, amount)?;
+    Ok(())
+}
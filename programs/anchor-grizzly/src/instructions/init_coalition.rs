@@ -0,0 +1,47 @@
+// initialize a coalition of merchants sharing one points mint
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct InitCoalition<'info> {
+    // coalition authority, governs merchant admission
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // initialize a new coalition account
+    #[account(
+        init,
+        seeds = [COALITION_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        payer = authority,
+        space = Coalition::LEN
+    )]
+    pub coalition: Account<'info, Coalition>,
+
+    // create mint to represent coalition points
+    #[account(
+        init,
+        seeds = [COALITION_POINTS_SEED.as_bytes(), coalition.key().as_ref()],
+        bump,
+        payer = authority,
+        mint::decimals = decimals,
+        mint::authority = coalition_points_mint,
+    )]
+    pub coalition_points_mint: Account<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn init_coalition_handler(ctx: Context<InitCoalition>, decimals: u8) -> Result<()> {
+    require!(
+        decimals <= MAX_POINTS_DECIMALS,
+        GrizzlyError::InvalidPointsDecimals
+    );
+
+    // store authority and points mint in coalition account
+    ctx.accounts.coalition.authority = ctx.accounts.authority.key();
+    ctx.accounts.coalition.points_mint = ctx.accounts.coalition_points_mint.key();
+    Ok(())
+}
@@ -12,20 +12,19 @@ pub struct InitRewardPoints<'info> {
         mut,
         seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
         bump,
-        constraint = merchant.authority == authority.key()
+        constraint = merchant.authority == authority.key(),
+        constraint = !merchant.reward_points_initialized @ GrizzlyError::RewardPointsAlreadyInitialized
     )]
     pub merchant: Account<'info, MerchantState>,
 
-    // create mint to represent reward points for merchant
+    /// CHECK: mint to represent reward points for merchant, created in handler
+    /// anchor init would run before the merchant's init flag constraint and fail on account creation
     #[account(
-        init,
+        mut,
         seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
         bump,
-        payer = authority,
-        mint::decimals = 6,
-        mint::authority = reward_points_mint,
     )]
-    pub reward_points_mint: Account<'info, Mint>,
+    pub reward_points_mint: UncheckedAccount<'info>,
 
     // create metadata account for reward points mint
     /// CHECK: initialize metadata account for reward points mint via CPI to token-metadata program
@@ -40,18 +39,33 @@ pub struct InitRewardPoints<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn init_reward_points_handler(
     ctx: Context<InitRewardPoints>,
     reward_points_basis_points: u16,
+    decimals: u8,
+    max_points_supply: u64,
+    non_transferable: bool,
     uri: String,
     name: String,
     symbol: String,
 ) -> Result<()> {
+    require!(
+        decimals <= MAX_POINTS_DECIMALS,
+        GrizzlyError::InvalidPointsDecimals
+    );
+
+    // mark reward points initialized, see merchant lifecycle in state.rs
+    ctx.accounts.merchant.reward_points_initialized = true;
     // update merchant account with reward points mint and basis points (% minted as reward points based on customer checkout amount)
     ctx.accounts.merchant.reward_points_mint = ctx.accounts.reward_points_mint.key();
     ctx.accounts.merchant.reward_points_basis_points = reward_points_basis_points;
+    // max reward points supply, caps merchant's outstanding reward points liability
+    ctx.accounts.merchant.max_points_supply = max_points_supply;
+    // non-transferable reward points, customer token accounts kept frozen outside program instructions
+    ctx.accounts.merchant.non_transferable_points = non_transferable;
 
-    // reward points mint PDA used to sign for metadata account creation CPI
+    // reward points mint PDA used to sign for mint and metadata account creation CPIs
     let merchant = ctx.accounts.merchant.key();
     let signer_seeds: &[&[&[u8]]] = &[&[
         REWARD_POINTS_SEED.as_bytes(),
@@ -59,6 +73,16 @@ pub fn init_reward_points_handler(
         &[*ctx.bumps.get("reward_points_mint").unwrap()],
     ]];
 
+    // create reward points mint, PDA is mint and freeze authority
+    create_pda_mint(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.reward_points_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        decimals,
+        signer_seeds,
+    )?;
+
     // create metadata account for reward points mint
     create_metadata_accounts_v3(
         CpiContext::new_with_signer(
@@ -0,0 +1,61 @@
+// create the membership account for a loyalty NFT minted by an earlier program version
+// loyalty NFTs minted before memberships were added have none, so the merchant can't revoke, renew
+// or discount them until it is created, the member keeps their lifetime membership (expiry 0)
+use crate::*;
+
+#[derive(Accounts)]
+pub struct MigrateMembership<'info> {
+    // authority of merchant account, pays for the membership account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    /// CHECK: used for customer loyalty nft mint PDA seed
+    pub customer: SystemAccount<'info>,
+
+    // customer's loyalty nft mint, the PDA is only created by create_nft_in_collection
+    #[account(
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    // membership account for loyalty nft, fails if the nft already has one
+    #[account(
+        init,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump,
+        payer = authority,
+        space = Membership::LEN
+    )]
+    pub membership: Account<'info, Membership>,
+
+    // merchant's member nft metadata template, assigns the next member number
+    #[account(
+        mut,
+        seeds = [MEMBER_TEMPLATE_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub member_template: Account<'info, MemberTemplate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_membership_handler(ctx: Context<MigrateMembership>) -> Result<()> {
+    // sequential member number
+    let member_template = &mut ctx.accounts.member_template;
+    member_template.member_count = member_template.member_count.checked_add(1).unwrap();
+
+    let membership = &mut ctx.accounts.membership;
+    membership.merchant = ctx.accounts.merchant.key();
+    membership.nft_mint = ctx.accounts.nft_mint.key();
+    membership.member_number = member_template.member_count;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// resize a merchant account created by an earlier program version to the current MerchantState::LEN
+// fields added since are zero initialized, merchants created before init flags and max points supply
+// existed get flags derived from their mints and an uncapped points supply
+// safe to run on a current merchant account, which is left unchanged
+use crate::*;
+
+#[derive(Accounts)]
+pub struct MigrateMerchant<'info> {
+    // authority of merchant account, pays for the larger account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: older layouts can't be deserialized as MerchantState, loaded in handler after realloc
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub merchant: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_merchant_handler(ctx: Context<MigrateMerchant>) -> Result<()> {
+    let merchant_info = ctx.accounts.merchant.to_account_info();
+
+    // top up rent exemption for the larger account, then grow it with the new fields zeroed
+    if merchant_info.data_len() < MerchantState::LEN {
+        let rent_exempt = Rent::get()?.minimum_balance(MerchantState::LEN);
+        let top_up = rent_exempt.saturating_sub(merchant_info.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: merchant_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        merchant_info.realloc(MerchantState::LEN, true)?;
+    }
+
+    let mut merchant = MerchantState::try_deserialize(&mut &merchant_info.data.borrow()[..])?;
+    require_keys_eq!(
+        merchant.authority,
+        ctx.accounts.authority.key(),
+        GrizzlyError::InvalidMerchantAccount
+    );
+
+    // reward points initialized before init flags and max points supply were added
+    if !merchant.reward_points_initialized && merchant.reward_points_mint != Pubkey::default() {
+        merchant.reward_points_initialized = true;
+        if merchant.max_points_supply == 0 {
+            merchant.max_points_supply = u64::MAX;
+        }
+    }
+    // loyalty collection created before init flags were added
+    if !merchant.loyalty_collection_initialized
+        && merchant.loyalty_collection_mint != Pubkey::default()
+    {
+        merchant.loyalty_collection_initialized = true;
+    }
+
+    merchant.try_serialize(&mut &mut merchant_info.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -51,6 +51,23 @@ pub fn mint_reward_points_handler(ctx: Context<MintRewardPoints>, amount: u64) -
         &[*ctx.bumps.get("reward_points_mint").unwrap()],
     ]];
 
+    // check reward points max supply
+    ctx.accounts.merchant.check_points_supply(
+        merchant,
+        ctx.accounts.reward_points_mint.supply,
+        amount,
+    )?;
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
     // mint reward points to customer
     msg!("Minting Reward Points Tokens");
     let cpi_ctx = CpiContext::new_with_signer(
@@ -63,5 +80,15 @@ pub fn mint_reward_points_handler(ctx: Context<MintRewardPoints>, amount: u64) -
         signer_seeds,
     );
     mint_to(cpi_ctx, amount)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
     Ok(())
 }
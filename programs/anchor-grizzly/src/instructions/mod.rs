@@ -1,17 +1,97 @@
+pub use admit_coalition_merchant::*;
+pub use cancel_pending_points::*;
 pub use create_collection_nft::*;
+pub use create_member_master::*;
 pub use create_nft_in_collection::*;
+pub use fund_points_vault::*;
+pub use init_coalition::*;
 pub use init_merchant::*;
 pub use init_reward_points::*;
+pub use migrate_membership::*;
+pub use migrate_merchant::*;
 pub use mint_reward_points::*;
+pub use raise_max_points_supply::*;
+pub use redeem_coalition_points::*;
+pub use redeem_perk::*;
+pub use redeem_points_for_cash::*;
+pub use redeem_voucher::*;
+pub use refresh_membership_metadata::*;
+pub use reissue_loyalty_nft::*;
+pub use renew_membership::*;
+pub use revoke_membership::*;
+pub use set_exchange_rate::*;
+pub use stake_points::*;
+pub use swap_points::*;
 pub use transaction::*;
+pub use unlock_loyalty_nft::*;
+pub use unstake_points::*;
+pub use update_cash_back::*;
+pub use update_collection_metadata::*;
+pub use update_loyalty_levels::*;
 pub use update_loyalty_points::*;
+pub use update_member_template::*;
+pub use update_membership_config::*;
+pub use update_membership_term::*;
+pub use update_perks::*;
+pub use update_points_vesting::*;
+pub use update_print_edition_membership::*;
+pub use update_programmable_loyalty_nft::*;
 pub use update_reward_points::*;
+pub use update_reward_points_metadata::*;
+pub use update_soulbound_loyalty_nft::*;
+pub use update_staking::*;
+pub use update_streak_tiers::*;
+pub use update_voucher_signer::*;
+pub use upgrade_loyalty_nft::*;
+pub use vest_points::*;
+pub use withdraw_collection_nft::*;
+pub use withdraw_points_vault::*;
 
+mod admit_coalition_merchant;
+mod cancel_pending_points;
 mod create_collection_nft;
+mod create_member_master;
 mod create_nft_in_collection;
+mod fund_points_vault;
+mod init_coalition;
 mod init_merchant;
 mod init_reward_points;
+mod migrate_membership;
+mod migrate_merchant;
 mod mint_reward_points;
+mod raise_max_points_supply;
+mod redeem_coalition_points;
+mod redeem_perk;
+mod redeem_points_for_cash;
+mod redeem_voucher;
+mod refresh_membership_metadata;
+mod reissue_loyalty_nft;
+mod renew_membership;
+mod revoke_membership;
+mod set_exchange_rate;
+mod stake_points;
+mod swap_points;
 mod transaction;
+mod unlock_loyalty_nft;
+mod unstake_points;
+mod update_cash_back;
+mod update_collection_metadata;
+mod update_loyalty_levels;
 mod update_loyalty_points;
+mod update_member_template;
+mod update_membership_config;
+mod update_membership_term;
+mod update_perks;
+mod update_points_vesting;
+mod update_print_edition_membership;
+mod update_programmable_loyalty_nft;
 mod update_reward_points;
+mod update_reward_points_metadata;
+mod update_soulbound_loyalty_nft;
+mod update_staking;
+mod update_streak_tiers;
+mod update_voucher_signer;
+mod upgrade_loyalty_nft;
+mod vest_points;
+mod withdraw_collection_nft;
+mod withdraw_points_vault;
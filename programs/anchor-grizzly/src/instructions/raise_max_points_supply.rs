@@ -0,0 +1,30 @@
+// raise reward points max supply, the cap can only be increased
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RaiseMaxPointsSupply<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn raise_max_points_supply_handler(
+    ctx: Context<RaiseMaxPointsSupply>,
+    max_points_supply: u64,
+) -> Result<()> {
+    require!(
+        max_points_supply > ctx.accounts.merchant.max_points_supply,
+        GrizzlyError::MaxPointsSupplyNotIncreased
+    );
+    ctx.accounts.merchant.max_points_supply = max_points_supply;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// redeem coalition points at a member merchant, burns customer's coalition points
+// merchant authority co-signs so redemptions recorded in the coalition ledger are approved
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RedeemCoalitionPoints<'info> {
+    // customer redeeming coalition points
+    pub customer: Signer<'info>,
+
+    // authority of merchant account accepting the redemption
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // coalition points mint
+    #[account(
+        mut,
+        seeds = [COALITION_POINTS_SEED.as_bytes(), merchant.coalition.as_ref()],
+        bump,
+    )]
+    pub coalition_points_mint: Account<'info, Mint>,
+
+    // merchant's coalition ledger
+    #[account(
+        mut,
+        seeds = [COALITION_MEMBER_SEED.as_bytes(), merchant.coalition.as_ref(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub coalition_member: Account<'info, CoalitionMember>,
+
+    // customer's coalition points token account
+    #[account(
+        mut,
+        associated_token::mint = coalition_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_coalition_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn redeem_coalition_points_handler(
+    ctx: Context<RedeemCoalitionPoints>,
+    points: u64,
+) -> Result<()> {
+    // burn customer's coalition points
+    msg!("Burning Coalition Points Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.coalition_points_mint.to_account_info(),
+            from: ctx
+                .accounts
+                .customer_coalition_token_account
+                .to_account_info(),
+            authority: ctx.accounts.customer.to_account_info(),
+        },
+    );
+    burn(cpi_ctx, points)?;
+
+    // record coalition points redeemed at merchant, used to settle between merchants
+    ctx.accounts.coalition_member.points_redeemed = ctx
+        .accounts
+        .coalition_member
+        .points_redeemed
+        .checked_add(points)
+        .unwrap();
+
+    emit!(CoalitionPointsRedeemed {
+        coalition: ctx.accounts.merchant.coalition,
+        merchant: ctx.accounts.merchant.key(),
+        customer: ctx.accounts.customer.key(),
+        points,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+// merchant redeems member nft perks at checkout, calls token metadata utilize
+// merchant account PDA was approved as use authority when the member nft was minted
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RedeemPerk<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account, use authority of member nfts
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    /// CHECK: member redeeming perk, owner of the member nft
+    pub customer: UncheckedAccount<'info>,
+
+    // member loyalty nft mint
+    #[account(mut)]
+    pub nft_mint: Account<'info, Mint>,
+
+    // member token account holding loyalty nft
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = customer,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: member loyalty nft metadata account
+    #[account(
+        mut,
+        address=find_metadata_account(&nft_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: use authority record approving merchant account to redeem member nft perks
+    #[account(
+        mut,
+        address=find_use_authority_account(&nft_mint.key(), &merchant.key()).0
+    )]
+    pub use_authority_record: UncheckedAccount<'info>,
+
+    /// CHECK: token metadata program burner PDA, utilize only checks the use authority record
+    /// when the burner account is also passed
+    #[account(
+        address=find_program_as_burner_account().0
+    )]
+    pub burner: UncheckedAccount<'info>,
+
+    // membership account for loyalty nft
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump,
+        constraint = membership.merchant == merchant.key(),
+        constraint = !membership.revoked @ GrizzlyError::RevokedMembership
+    )]
+    pub membership: Account<'info, Membership>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn redeem_perk_handler(ctx: Context<RedeemPerk>, uses: u64) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .membership
+            .is_expired(Clock::get()?.unix_timestamp),
+        GrizzlyError::MembershipExpired
+    );
+
+    // merchant PDA signs as use authority
+    let authority = ctx.accounts.authority.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
+
+    // anchor-spl has no wrapper for utilize
+    let ix = utilize(
+        ctx.accounts.token_metadata_program.key(),
+        ctx.accounts.metadata_account.key(),
+        ctx.accounts.token_account.key(),
+        ctx.accounts.nft_mint.key(),
+        Some(ctx.accounts.use_authority_record.key()),
+        ctx.accounts.merchant.key(),
+        ctx.accounts.customer.key(),
+        Some(ctx.accounts.burner.key()),
+        uses,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.metadata_account.to_account_info(),
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.nft_mint.to_account_info(),
+            ctx.accounts.merchant.to_account_info(),
+            ctx.accounts.customer.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.associated_token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.use_authority_record.to_account_info(),
+            ctx.accounts.burner.to_account_info(),
+            ctx.accounts.token_metadata_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    let membership = &mut ctx.accounts.membership;
+    membership.perks_redeemed = membership.perks_redeemed.checked_add(uses).unwrap();
+
+    emit!(PerkRedeemed {
+        merchant: ctx.accounts.merchant.key(),
+        customer: ctx.accounts.customer.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        uses,
+        perks_redeemed: membership.perks_redeemed,
+    });
+    Ok(())
+}
@@ -0,0 +1,159 @@
+// redeem reward points for "usdc" cash back, reward points burned and "usdc" paid from points vault
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RedeemPointsForCash<'info> {
+    // customer redeeming reward points
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    // customer's reward points token account
+    #[account(
+        mut,
+        associated_token::mint = reward_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    // "usdc" mint account
+    #[account(
+        address = USDC_MINT_PLACEHOLDER
+    )]
+    pub usdc_mint_placeholder: Account<'info, Mint>,
+
+    // merchant's "usdc" points vault
+    #[account(
+        mut,
+        seeds = [POINTS_VAULT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub points_vault: Account<'info, TokenAccount>,
+
+    // customer's "usdc" token account receiving cash back
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        constraint = customer_usdc_token_account.owner == customer.key()
+    )]
+    pub customer_usdc_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn redeem_points_for_cash_handler(
+    ctx: Context<RedeemPointsForCash>,
+    points: u64,
+) -> Result<()> {
+    let merchant = &ctx.accounts.merchant;
+    require!(
+        merchant.cash_back_basis_points > 0,
+        GrizzlyError::CashBackDisabled
+    );
+    require!(
+        points >= merchant.min_redemption_points,
+        GrizzlyError::BelowMinRedemption
+    );
+
+    // calculate cash back, normalized from reward points decimals to "usdc" decimals
+    let payout = convert_amount(
+        points,
+        merchant.cash_back_basis_points,
+        ctx.accounts.reward_points_mint.decimals,
+        ctx.accounts.usdc_mint_placeholder.decimals,
+    )?;
+    require!(payout > 0, GrizzlyError::BelowMinRedemption);
+    require!(
+        payout <= ctx.accounts.points_vault.amount,
+        GrizzlyError::InsufficientPointsVault
+    );
+
+    // reward points mint PDA is also freeze authority
+    let merchant_key = ctx.accounts.merchant.key();
+    let points_signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant_key.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            points_signer_seeds,
+        )?;
+    }
+
+    // burn customer's reward points
+    msg!("Burning Reward Points Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.reward_points_mint.to_account_info(),
+            from: ctx.accounts.customer_reward_token_account.to_account_info(),
+            authority: ctx.accounts.customer.to_account_info(),
+        },
+    );
+    burn(cpi_ctx, points)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            points_signer_seeds,
+        )?;
+    }
+
+    // merchant PDA is points vault authority
+    let authority = ctx.accounts.authority.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
+
+    // transfer cash back from points vault to customer
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.points_vault.to_account_info(),
+            authority: ctx.accounts.merchant.to_account_info(),
+            to: ctx.accounts.customer_usdc_token_account.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer(cpi_ctx, payout)?;
+
+    emit!(PointsRedeemedForCash {
+        merchant: ctx.accounts.merchant.key(),
+        customer: ctx.accounts.customer.key(),
+        points,
+        payout,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+// redeem reward points voucher signed off-chain by merchant's voucher signer (e.g. POS backend)
+// the transaction must include an Ed25519 program instruction verifying the voucher signature
+// immediately before this instruction
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(points: u64, nonce: u64)]
+pub struct RedeemVoucher<'info> {
+    // customer redeeming voucher
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    // init customer's reward points token account if one does not exist
+    #[account(
+        init_if_needed,
+        payer = customer,
+        associated_token::mint = reward_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    // voucher nonce account, init fails if nonce was already redeemed (replay protection)
+    #[account(
+        init,
+        seeds = [VOUCHER_SEED.as_bytes(), merchant.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump,
+        payer = customer,
+        space = VoucherNonce::LEN
+    )]
+    pub voucher_nonce: Account<'info, VoucherNonce>,
+
+    /// CHECK: instructions sysvar, used to load the Ed25519 signature verification instruction
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn redeem_voucher_handler(
+    ctx: Context<RedeemVoucher>,
+    points: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Result<()> {
+    let voucher_signer = ctx.accounts.merchant.voucher_signer;
+    require_keys_neq!(
+        voucher_signer,
+        Pubkey::default(),
+        GrizzlyError::VoucherSignerNotSet
+    );
+    require!(
+        Clock::get()?.unix_timestamp <= expiry,
+        GrizzlyError::VoucherExpired
+    );
+
+    // voucher payload signed off-chain: merchant, customer, points, nonce, expiry
+    let merchant = ctx.accounts.merchant.key();
+    let customer = ctx.accounts.customer.key();
+    let mut message = Vec::with_capacity(VOUCHER_MESSAGE_LEN);
+    message.extend_from_slice(merchant.as_ref());
+    message.extend_from_slice(customer.as_ref());
+    message.extend_from_slice(&points.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+
+    // check the preceding instruction verified the voucher signature
+    verify_ed25519_instruction(&ctx.accounts.instructions, &voucher_signer, &message)?;
+
+    // record redeemed nonce
+    ctx.accounts.voucher_nonce.merchant = merchant;
+    ctx.accounts.voucher_nonce.customer = customer;
+    ctx.accounts.voucher_nonce.nonce = nonce;
+
+    // reward points mint PDA is also mint authority
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // check reward points max supply
+    ctx.accounts.merchant.check_points_supply(
+        merchant,
+        ctx.accounts.reward_points_mint.supply,
+        points,
+    )?;
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    // mint reward points to customer
+    msg!("Minting Reward Points Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.reward_points_mint.to_account_info(),
+            to: ctx.accounts.customer_reward_token_account.to_account_info(),
+            authority: ctx.accounts.reward_points_mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    mint_to(cpi_ctx, points)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    Ok(())
+}
+
+// size of voucher payload: merchant (32) + customer (32) + points (8) + nonce (8) + expiry (8)
+const VOUCHER_MESSAGE_LEN: usize = 32 + 32 + 8 + 8 + 8;
+
+// Ed25519 instruction data layout: num signatures (1) + padding (1), followed by offsets (14)
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+
+// check the instruction before this one is an Ed25519 program instruction verifying a single
+// signature by `signer` over `message`, with all data contained in that same instruction
+fn verify_ed25519_instruction(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = sysvar_instructions::load_current_index_checked(instructions)?;
+    require!(
+        current_index > 0,
+        GrizzlyError::InvalidVoucherSignatureInstruction
+    );
+    let ix = sysvar_instructions::load_instruction_at_checked(
+        (current_index - 1) as usize,
+        instructions,
+    )?;
+
+    let data = &ix.data;
+    require!(
+        ix.program_id == ed25519_program::ID
+            && ix.accounts.is_empty()
+            && data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_LEN
+            && data[0] == 1,
+        GrizzlyError::InvalidVoucherSignatureInstruction
+    );
+
+    let read_u16 =
+        |offset: usize| -> usize { u16::from_le_bytes([data[offset], data[offset + 1]]) as usize };
+    let offsets = ED25519_OFFSETS_START;
+    let signature_ix_index = read_u16(offsets + 2);
+    let public_key_offset = read_u16(offsets + 4);
+    let public_key_ix_index = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8);
+    let message_size = read_u16(offsets + 10);
+    let message_ix_index = read_u16(offsets + 12);
+
+    // signature, public key and message must all come from the Ed25519 instruction itself
+    let this_ix = u16::MAX as usize;
+    require!(
+        signature_ix_index == this_ix
+            && public_key_ix_index == this_ix
+            && message_ix_index == this_ix,
+        GrizzlyError::InvalidVoucherSignatureInstruction
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(GrizzlyError::InvalidVoucherSignatureInstruction)?;
+    require!(
+        public_key == signer.as_ref(),
+        GrizzlyError::VoucherSignerMismatch
+    );
+
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(GrizzlyError::InvalidVoucherSignatureInstruction)?;
+    require!(
+        signed_message == message,
+        GrizzlyError::VoucherPayloadMismatch
+    );
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+// crank updating member nft uri to match membership state, callable by anyone
+// expired memberships show the template's expired uri, renewed memberships get their uri back
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RefreshMembershipMetadata<'info> {
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program, update authority of member nfts
+    // pays for programmable member nft updates
+    #[account(
+        mut,
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    // merchant's member nft metadata template
+    #[account(
+        seeds = [MEMBER_TEMPLATE_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub member_template: Account<'info, MemberTemplate>,
+
+    // merchant's loyalty level table, required for upgraded member nfts
+    #[account(
+        seeds = [LOYALTY_LEVELS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub loyalty_levels: Option<Account<'info, LoyaltyLevels>>,
+
+    // member loyalty nft mint
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: member loyalty nft metadata account
+    #[account(
+        mut,
+        address=find_metadata_account(&nft_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    // membership account for loyalty nft
+    #[account(
+        seeds = [MEMBERSHIP_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump,
+        constraint = membership.merchant == merchant.key(),
+    )]
+    pub membership: Account<'info, Membership>,
+
+    /// CHECK: instructions sysvar, required for programmable member nfts
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, required if the member nft has a rule set
+    #[account(address = TOKEN_AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: rule set the member nft was minted with, checked against the nft's metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    // required for programmable member nfts
+    pub system_program: Option<Program<'info, System>>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn refresh_membership_metadata_handler(ctx: Context<RefreshMembershipMetadata>) -> Result<()> {
+    require_mutable_loyalty_nft(&ctx.accounts.metadata_account)?;
+
+    // uri for current membership state
+    let uri = ctx.accounts.member_template.membership_uri(
+        &ctx.accounts.membership,
+        ctx.accounts.loyalty_levels.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let mut data = loyalty_nft_data(&ctx.accounts.metadata_account)?;
+    if data.uri == uri {
+        return Ok(());
+    }
+    data.uri = uri;
+
+    // PDA for signing as member nft update authority
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+
+    update_loyalty_nft_data(
+        UpdateLoyaltyNft {
+            loyalty_collection_mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            nft_mint: ctx.accounts.nft_mint.to_account_info(),
+            metadata_account: ctx.accounts.metadata_account.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx
+                .accounts
+                .system_program
+                .as_ref()
+                .map(|program| program.to_account_info()),
+            sysvar_instructions: ctx
+                .accounts
+                .sysvar_instructions
+                .as_ref()
+                .map(|account| account.to_account_info()),
+            authorization_rules_program: ctx
+                .accounts
+                .authorization_rules_program
+                .as_ref()
+                .map(|program| program.to_account_info()),
+            authorization_rules: ctx
+                .accounts
+                .authorization_rules
+                .as_ref()
+                .map(|account| account.to_account_info()),
+        },
+        data,
+        signer_seeds,
+    )
+}
@@ -0,0 +1,308 @@
+// mint a new loyalty NFT to a customer who burned theirs
+// each reissue uses a new versioned mint seed, the previous version mint must have been burned
+// note this instruction requires requesting extra compute units
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(version: u32)]
+pub struct ReissueLoyaltyNft<'info> {
+    // customer getting reissued NFT
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// CHECK: Used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program
+    #[account(
+        mut,
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    // collection metadata account
+    /// CHECK:
+    #[account(
+        mut,
+        address=find_metadata_account(&loyalty_collection_mint.key()).0
+    )]
+    pub collection_metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: collection master edition account
+    #[account(
+        mut,
+        address=find_master_edition_account(&loyalty_collection_mint.key()).0
+    )]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    // previous version loyalty nft mint, all tokens burned
+    #[account(
+        constraint = burned_nft_mint.supply == 0 @ GrizzlyError::LoyaltyNftNotBurned
+    )]
+    pub burned_nft_mint: Account<'info, Mint>,
+
+    /// CHECK: previous version metadata account, closed if the nft was burned through token metadata
+    #[account(
+        mut,
+        address=find_metadata_account(&burned_nft_mint.key()).0
+    )]
+    pub burned_metadata_account: UncheckedAccount<'info>,
+
+    // previous version membership, a revoked membership can not be reissued
+    // reissued nfts are minted as regular member nfts, so print edition member nfts can not be reissued
+    #[account(
+        seeds = [MEMBERSHIP_SEED.as_bytes(), burned_nft_mint.key().as_ref()],
+        bump,
+        constraint = !burned_membership.revoked @ GrizzlyError::RevokedMembership,
+        constraint = !burned_membership.print_edition @ GrizzlyError::PrintEditionReissueUnsupported
+    )]
+    pub burned_membership: Box<Account<'info, Membership>>,
+
+    // versioned mint address for reissued customer loyalty nft
+    #[account(
+        init,
+        seeds = [
+            LOYALTY_NFT_SEED.as_bytes(),
+            merchant.key().as_ref(),
+            customer.key().as_ref(),
+            version.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = customer,
+        mint::decimals = 0,
+        mint::authority = loyalty_collection_mint,
+        mint::freeze_authority = loyalty_collection_mint
+    )]
+    pub customer_nft_mint: Account<'info, Mint>,
+
+    // create customer metadata account for loyalty nft
+    /// CHECK:
+    #[account(
+        mut,
+        address=find_metadata_account(&customer_nft_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: master edition account
+    #[account(
+        mut,
+        address=find_master_edition_account(&customer_nft_mint.key()).0
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    // customer token account for loyalty nft
+    #[account(
+        init_if_needed,
+        payer = customer,
+        associated_token::mint = customer_nft_mint,
+        associated_token::authority = customer
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: use authority record approving merchant account to redeem member nft perks
+    #[account(
+        mut,
+        address=find_use_authority_account(&customer_nft_mint.key(), &merchant.key()).0
+    )]
+    pub use_authority_record: UncheckedAccount<'info>,
+
+    /// CHECK: token metadata program burner PDA, required by approve_use_authority
+    #[account(
+        address=find_program_as_burner_account().0
+    )]
+    pub burner: UncheckedAccount<'info>,
+
+    // membership account for loyalty nft
+    #[account(
+        init,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), customer_nft_mint.key().as_ref()],
+        bump,
+        payer = customer,
+        space = Membership::LEN
+    )]
+    pub membership: Box<Account<'info, Membership>>,
+
+    // merchant's member nft metadata template
+    #[account(
+        seeds = [MEMBER_TEMPLATE_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub member_template: Box<Account<'info, MemberTemplate>>,
+
+    // merchant's loyalty level table, required to reissue upgraded member nfts
+    #[account(
+        seeds = [LOYALTY_LEVELS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub loyalty_levels: Option<Box<Account<'info, LoyaltyLevels>>>,
+
+    /// CHECK: token record PDA of the customer's token account, required for programmable member nfts
+    #[account(
+        mut,
+        address=find_token_record_account(&customer_nft_mint.key(), &token_account.key()).0
+    )]
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar, required for programmable member nfts
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, required if the merchant set a rule set
+    #[account(address = TOKEN_AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: merchant's rule set for programmable member nfts, checked by token metadata program
+    #[account(address = merchant.loyalty_nft_rule_set)]
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn reissue_loyalty_nft_handler(ctx: Context<ReissueLoyaltyNft>, version: u32) -> Result<()> {
+    let merchant = ctx.accounts.merchant.key();
+    let customer = ctx.accounts.customer.key();
+
+    // version 1 replaces the original nft minted by create_nft_in_collection
+    let previous_version = version
+        .checked_sub(1)
+        .ok_or(GrizzlyError::InvalidLoyaltyNftVersion)?;
+    let previous_version_bytes = previous_version.to_le_bytes();
+    let mut burned_nft_seeds = vec![
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        customer.as_ref(),
+    ];
+    if previous_version > 0 {
+        burned_nft_seeds.push(previous_version_bytes.as_ref());
+    }
+    let (burned_nft_mint, _) = Pubkey::find_program_address(&burned_nft_seeds, ctx.program_id);
+    require_keys_eq!(
+        ctx.accounts.burned_nft_mint.key(),
+        burned_nft_mint,
+        GrizzlyError::InvalidLoyaltyNftVersion
+    );
+
+    // PDA for signing
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+    let authority = ctx.accounts.authority.key();
+    let merchant_signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
+
+    // burned with spl token burn, leftover metadata is still counted in collection size
+    let burned_metadata_account = ctx.accounts.burned_metadata_account.to_account_info();
+    if !burned_metadata_account.data_is_empty()
+        && burned_metadata_account.owner == &ctx.accounts.token_metadata_program.key()
+    {
+        let burned_metadata = MetadataState::from_account_info(&burned_metadata_account)?;
+        if matches!(burned_metadata.collection, Some(collection) if collection.verified) {
+            unverify_loyalty_nft(
+                &ctx.accounts.token_metadata_program.to_account_info(),
+                &burned_metadata_account,
+                &ctx.accounts.loyalty_collection_mint.to_account_info(),
+                &ctx.accounts.customer.to_account_info(),
+                &ctx.accounts.collection_metadata_account.to_account_info(),
+                &ctx.accounts.collection_master_edition.to_account_info(),
+                signer_seeds,
+            )?;
+        }
+    }
+
+    // burned through token metadata, collection size was already decremented
+    ctx.accounts
+        .merchant
+        .check_member_cap(loyalty_collection_size(
+            &ctx.accounts.collection_metadata_account,
+        )?)?;
+
+    // reissued membership keeps the member number, loyalty level, expiry and redeemed perks
+    // of the burned nft
+    let level = ctx.accounts.burned_membership.level;
+    let member_number = ctx.accounts.burned_membership.member_number;
+    let expiry = ctx.accounts.burned_membership.expiry;
+    let perks_redeemed = ctx.accounts.burned_membership.perks_redeemed;
+    let membership = &mut ctx.accounts.membership;
+    membership.merchant = merchant;
+    membership.nft_mint = ctx.accounts.customer_nft_mint.key();
+    membership.level = level;
+    membership.member_number = member_number;
+    membership.expiry = expiry;
+    membership.perks_redeemed = perks_redeemed;
+
+    // reissued nft shows the uri for its membership state (level or expired), like refresh_membership_metadata
+    let uri = ctx.accounts.member_template.membership_uri(
+        &ctx.accounts.membership,
+        ctx.accounts
+            .loyalty_levels
+            .as_deref()
+            .map(|levels| &**levels),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // member nfts inherit the collection's royalty and creators
+    let (seller_fee_basis_points, creators) = member_royalties(
+        &ctx.accounts.collection_metadata_account,
+        ctx.accounts.merchant.key(),
+    )?;
+
+    mint_loyalty_nft(
+        MintLoyaltyNft {
+            payer: ctx.accounts.customer.to_account_info(),
+            owner: ctx.accounts.customer.to_account_info(),
+            loyalty_collection_mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            collection_metadata_account: ctx.accounts.collection_metadata_account.to_account_info(),
+            collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+            nft_mint: ctx.accounts.customer_nft_mint.to_account_info(),
+            metadata_account: ctx.accounts.metadata_account.to_account_info(),
+            master_edition: ctx.accounts.master_edition.to_account_info(),
+            token_account: ctx.accounts.token_account.to_account_info(),
+            merchant: ctx.accounts.merchant.to_account_info(),
+            use_authority_record: ctx.accounts.use_authority_record.to_account_info(),
+            burner: ctx.accounts.burner.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            // print edition member nfts are rejected by the burned membership constraint
+            print_edition: None,
+            programmable: programmable_nft(
+                &ctx.accounts.merchant,
+                ctx.accounts.token_record.as_ref(),
+                ctx.accounts.sysvar_instructions.as_ref(),
+                &ctx.accounts.associated_token_program.to_account_info(),
+                ctx.accounts.authorization_rules_program.as_ref(),
+                ctx.accounts.authorization_rules.as_ref(),
+            )?,
+        },
+        DataV2 {
+            seller_fee_basis_points,
+            creators,
+            uses: ctx.accounts.merchant.member_perks(perks_redeemed),
+            uri,
+            ..ctx.accounts.member_template.member_data(member_number)
+        },
+        ctx.accounts.merchant.soulbound_loyalty_nft,
+        signer_seeds,
+        merchant_signer_seeds,
+    )
+}
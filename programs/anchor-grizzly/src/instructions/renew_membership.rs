@@ -0,0 +1,91 @@
+// customer pays merchant's renewal price to extend membership by the membership duration
+// renewing an expired membership starts the new term now
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RenewMembership<'info> {
+    // customer renewing membership
+    pub customer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's payment destination
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        address = merchant.payment_destination,
+    )]
+    pub payment_destination: Account<'info, TokenAccount>,
+
+    // customer's "usdc" token account
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        constraint = customer_usdc_token_account.owner == customer.key()
+    )]
+    pub customer_usdc_token_account: Account<'info, TokenAccount>,
+
+    // customer loyalty nft mint
+    pub nft_mint: Account<'info, Mint>,
+
+    // customer token account holding loyalty nft
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = customer,
+        constraint = nft_token_account.amount == 1
+    )]
+    pub nft_token_account: Account<'info, TokenAccount>,
+
+    // membership account for loyalty nft
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump,
+        constraint = membership.merchant == merchant.key(),
+        constraint = !membership.revoked @ GrizzlyError::RevokedMembership
+    )]
+    pub membership: Account<'info, Membership>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn renew_membership_handler(ctx: Context<RenewMembership>) -> Result<()> {
+    let merchant = &ctx.accounts.merchant;
+    require!(
+        merchant.membership_duration > 0,
+        GrizzlyError::MembershipDoesNotExpire
+    );
+
+    // memberships minted while memberships didn't expire (expiry 0) stay lifetime memberships
+    require!(
+        ctx.accounts.membership.expiry != 0,
+        GrizzlyError::MembershipDoesNotExpire
+    );
+
+    // transfer renewal price from customer to merchant
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.customer_usdc_token_account.to_account_info(),
+            authority: ctx.accounts.customer.to_account_info(),
+            to: ctx.accounts.payment_destination.to_account_info(),
+        },
+    );
+    transfer(cpi_ctx, merchant.renewal_price)?;
+
+    // extend from current expiry, or from now if already expired
+    let now = Clock::get()?.unix_timestamp;
+    let membership = &mut ctx.accounts.membership;
+    membership.expiry = merchant.membership_expiry(membership.expiry.max(now))?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+// merchant revokes a member's loyalty NFT
+// the NFT is unverified from the collection (decrementing collection size), the member's token
+// account is frozen and the membership is flagged revoked so the checkout discount is refused
+// programmable member nfts stay transferable under their rule set, only the membership is revoked
+use crate::*;
+
+#[derive(Accounts)]
+pub struct RevokeMembership<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program
+    #[account(
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    // collection metadata account
+    /// CHECK:
+    #[account(
+        mut,
+        address=find_metadata_account(&loyalty_collection_mint.key()).0
+    )]
+    pub collection_metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: collection master edition account
+    #[account(
+        address=find_master_edition_account(&loyalty_collection_mint.key()).0
+    )]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    // member loyalty nft mint
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: member loyalty nft metadata account
+    #[account(
+        mut,
+        address=find_metadata_account(&nft_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: member loyalty nft master edition account, freeze authority of nft mint
+    #[account(
+        address=find_master_edition_account(&nft_mint.key()).0
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    // member token account holding loyalty nft
+    #[account(
+        mut,
+        token::mint = nft_mint,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    // membership account for loyalty nft
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump,
+        constraint = membership.merchant == merchant.key(),
+        constraint = !membership.revoked @ GrizzlyError::RevokedMembership
+    )]
+    pub membership: Account<'info, Membership>,
+
+    /// CHECK: instructions sysvar, required for programmable member nfts
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    // required for programmable member nfts
+    pub system_program: Option<Program<'info, System>>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn revoke_membership_handler(ctx: Context<RevokeMembership>) -> Result<()> {
+    // PDA for signing as collection authority and token account delegate
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+
+    // unverify nft from collection, decrements collection size
+    if is_programmable_loyalty_nft(&ctx.accounts.metadata_account)? {
+        let (system_program, sysvar_instructions) = match (
+            ctx.accounts.system_program.as_ref(),
+            ctx.accounts.sysvar_instructions.as_ref(),
+        ) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return err!(GrizzlyError::ProgrammableLoyaltyNftAccountsRequired),
+        };
+        unverify_programmable_loyalty_nft(
+            &ctx.accounts.token_metadata_program.to_account_info(),
+            &ctx.accounts.metadata_account.to_account_info(),
+            &ctx.accounts.loyalty_collection_mint.to_account_info(),
+            &ctx.accounts.collection_metadata_account.to_account_info(),
+            &system_program.to_account_info(),
+            &sysvar_instructions.to_account_info(),
+            signer_seeds,
+        )?;
+    } else {
+        unverify_loyalty_nft(
+            &ctx.accounts.token_metadata_program.to_account_info(),
+            &ctx.accounts.metadata_account.to_account_info(),
+            &ctx.accounts.loyalty_collection_mint.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.collection_metadata_account.to_account_info(),
+            &ctx.accounts.collection_master_edition.to_account_info(),
+            signer_seeds,
+        )?;
+    }
+
+    // freeze member token account, the loyalty collection mint PDA is approved as delegate at mint
+    // skipped if already frozen (soulbound, programmable) or the member revoked the delegate
+    let token_account = &ctx.accounts.token_account;
+    if !token_account.is_frozen()
+        && token_account.delegate == COption::Some(ctx.accounts.loyalty_collection_mint.key())
+    {
+        freeze_delegated_account(CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            FreezeDelegatedAccount {
+                metadata: ctx.accounts.metadata_account.to_account_info(),
+                delegate: ctx.accounts.loyalty_collection_mint.to_account_info(),
+                token_account: ctx.accounts.token_account.to_account_info(),
+                edition: ctx.accounts.master_edition.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    ctx.accounts.membership.revoked = true;
+
+    emit!(MembershipRevoked {
+        merchant,
+        nft_mint: ctx.accounts.nft_mint.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// publish rate at which merchant accepts another merchant's reward points in exchange for its own
+use crate::*;
+
+#[derive(Accounts)]
+pub struct SetExchangeRate<'info> {
+    // authority of merchant account accepting the source merchant's reward points
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account, destination of swapped reward points
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant account whose reward points are accepted
+    #[account(
+        constraint = source_merchant.key() != merchant.key()
+    )]
+    pub source_merchant: Account<'info, MerchantState>,
+
+    // init exchange rate account if one does not exist
+    #[account(
+        init_if_needed,
+        seeds = [EXCHANGE_RATE_SEED.as_bytes(), merchant.key().as_ref(), source_merchant.key().as_ref()],
+        bump,
+        payer = authority,
+        space = ExchangeRate::LEN
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_exchange_rate_handler(
+    ctx: Context<SetExchangeRate>,
+    rate_basis_points: u16,
+    daily_volume_cap: u64,
+) -> Result<()> {
+    let exchange_rate = &mut ctx.accounts.exchange_rate;
+    // rate 0 disables a published exchange rate, a new exchange rate must accept points
+    require!(
+        rate_basis_points > 0 || exchange_rate.destination_merchant != Pubkey::default(),
+        GrizzlyError::InvalidExchangeRate
+    );
+    exchange_rate.destination_merchant = ctx.accounts.merchant.key();
+    exchange_rate.source_merchant = ctx.accounts.source_merchant.key();
+    exchange_rate.rate_basis_points = rate_basis_points;
+    exchange_rate.daily_volume_cap = daily_volume_cap;
+    Ok(())
+}
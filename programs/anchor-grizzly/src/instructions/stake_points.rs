@@ -0,0 +1,132 @@
+// stake customer reward points in a PDA vault, boosts reward points earned at checkout until unlock
+use crate::*;
+
+#[derive(Accounts)]
+pub struct StakePoints<'info> {
+    // customer staking reward points
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint
+    #[account(
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    // customer's reward points token account
+    #[account(
+        mut,
+        associated_token::mint = reward_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    // init customer's stake vault if one does not exist, stake vault PDA is its own authority
+    #[account(
+        init_if_needed,
+        seeds = [STAKE_VAULT_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+        payer = customer,
+        token::mint = reward_points_mint,
+        token::authority = stake_vault
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    // init customer account tracking staked reward points if one does not exist
+    #[account(
+        init_if_needed,
+        seeds = [CUSTOMER_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+        payer = customer,
+        space = CustomerState::LEN
+    )]
+    pub customer_state: Box<Account<'info, CustomerState>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn stake_points_handler(
+    ctx: Context<StakePoints>,
+    amount: u64,
+    lock_duration: i64,
+) -> Result<()> {
+    require!(lock_duration > 0, GrizzlyError::InvalidStakeDuration);
+
+    // reward points mint PDA is also freeze authority
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    // transfer reward points from customer to stake vault
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.customer_reward_token_account.to_account_info(),
+            authority: ctx.accounts.customer.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+        },
+    );
+    transfer(cpi_ctx, amount)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    // record stake, adding to an existing stake never shortens its lock
+    let unlock_timestamp = Clock::get()?
+        .unix_timestamp
+        .checked_add(lock_duration)
+        .unwrap();
+    let customer_state = &mut ctx.accounts.customer_state;
+    customer_state.merchant = merchant;
+    customer_state.customer = ctx.accounts.customer.key();
+    customer_state.staked_amount = customer_state.staked_amount.checked_add(amount).unwrap();
+    require!(
+        customer_state.staked_amount >= ctx.accounts.merchant.min_stake_amount,
+        GrizzlyError::StakeBelowMinimum
+    );
+    customer_state.stake_unlock_timestamp =
+        customer_state.stake_unlock_timestamp.max(unlock_timestamp);
+
+    // stake terms are fixed when the customer stakes, later staking updates by the merchant
+    // don't apply to the locked stake, adding to a stake accepts the merchant's current terms
+    customer_state.stake_boost_basis_points = ctx.accounts.merchant.stake_boost_basis_points;
+    customer_state.stake_penalty_basis_points =
+        ctx.accounts.merchant.early_unstake_penalty_basis_points;
+    Ok(())
+}
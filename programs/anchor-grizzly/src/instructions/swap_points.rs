@@ -0,0 +1,195 @@
+// swap customer's reward points from one merchant for another merchant's reward points
+// source reward points are burned, destination reward points minted at the published exchange rate
+use crate::*;
+
+#[derive(Accounts)]
+pub struct SwapPoints<'info> {
+    // customer swapping reward points
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    // merchant account whose reward points are burned
+    pub source_merchant: Box<Account<'info, MerchantState>>,
+
+    // merchant account whose reward points are minted
+    pub destination_merchant: Box<Account<'info, MerchantState>>,
+
+    // exchange rate published by destination merchant
+    #[account(
+        mut,
+        seeds = [EXCHANGE_RATE_SEED.as_bytes(), destination_merchant.key().as_ref(), source_merchant.key().as_ref()],
+        bump,
+    )]
+    pub exchange_rate: Account<'info, ExchangeRate>,
+
+    // source merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), source_merchant.key().as_ref()],
+        bump,
+        address = source_merchant.reward_points_mint,
+    )]
+    pub source_points_mint: Box<Account<'info, Mint>>,
+
+    // destination merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), destination_merchant.key().as_ref()],
+        bump,
+        address = destination_merchant.reward_points_mint,
+    )]
+    pub destination_points_mint: Box<Account<'info, Mint>>,
+
+    // customer's source reward points token account
+    #[account(
+        mut,
+        associated_token::mint = source_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_source_token_account: Box<Account<'info, TokenAccount>>,
+
+    // init customer's destination reward points token account if one does not exist
+    #[account(
+        init_if_needed,
+        payer = customer,
+        associated_token::mint = destination_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_destination_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn swap_points_handler(ctx: Context<SwapPoints>, amount: u64) -> Result<()> {
+    let exchange_rate = &mut ctx.accounts.exchange_rate;
+    require!(
+        exchange_rate.rate_basis_points > 0,
+        GrizzlyError::ExchangeRateDisabled
+    );
+
+    // reset daily volume on a new day, then check daily volume cap (in source reward points)
+    let day = Clock::get()?.unix_timestamp / SECONDS_PER_DAY;
+    if day != exchange_rate.current_day {
+        exchange_rate.current_day = day;
+        exchange_rate.volume_today = 0;
+    }
+    exchange_rate.volume_today = exchange_rate.volume_today.checked_add(amount).unwrap();
+    require!(
+        exchange_rate.volume_today <= exchange_rate.daily_volume_cap,
+        GrizzlyError::ExchangeVolumeCapExceeded
+    );
+
+    // calculate destination reward points, normalized between reward points mint decimals
+    let destination_amount = convert_amount(
+        amount,
+        exchange_rate.rate_basis_points,
+        ctx.accounts.source_points_mint.decimals,
+        ctx.accounts.destination_points_mint.decimals,
+    )?;
+    // swaps rounding down to 0 destination reward points would burn source points for nothing
+    require!(destination_amount > 0, GrizzlyError::SwapAmountTooSmall);
+
+    // check destination reward points max supply
+    let destination_merchant = ctx.accounts.destination_merchant.key();
+    ctx.accounts.destination_merchant.check_points_supply(
+        destination_merchant,
+        ctx.accounts.destination_points_mint.supply,
+        destination_amount,
+    )?;
+
+    // source reward points mint PDA is also freeze authority
+    let source_merchant = ctx.accounts.source_merchant.key();
+    let source_signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        source_merchant.as_ref(),
+        &[*ctx.bumps.get("source_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.source_merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.source_points_mint,
+            &ctx.accounts.customer_source_token_account,
+            source_signer_seeds,
+        )?;
+    }
+
+    // burn customer's source reward points
+    msg!("Burning Reward Points Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.source_points_mint.to_account_info(),
+            from: ctx.accounts.customer_source_token_account.to_account_info(),
+            authority: ctx.accounts.customer.to_account_info(),
+        },
+    );
+    burn(cpi_ctx, amount)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.source_merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.source_points_mint,
+            &ctx.accounts.customer_source_token_account,
+            source_signer_seeds,
+        )?;
+    }
+
+    // destination reward points mint PDA is also mint authority
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        destination_merchant.as_ref(),
+        &[*ctx.bumps.get("destination_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.destination_merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.destination_points_mint,
+            &ctx.accounts.customer_destination_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    // mint destination reward points to customer
+    msg!("Minting Reward Points Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.destination_points_mint.to_account_info(),
+            to: ctx
+                .accounts
+                .customer_destination_token_account
+                .to_account_info(),
+            authority: ctx.accounts.destination_points_mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    mint_to(cpi_ctx, destination_amount)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.destination_merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.destination_points_mint,
+            &ctx.accounts.customer_destination_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    emit!(PointsSwapped {
+        source_merchant,
+        destination_merchant,
+        customer: ctx.accounts.customer.key(),
+        source_amount: amount,
+        destination_amount,
+    });
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 // checkout transaction, customer pays merchant "usdc", customer also gets minted reward points
+// customers presenting a member loyalty NFT get the merchant's loyalty discount
 use crate::*;
 
 #[derive(Accounts)]
@@ -10,8 +11,9 @@ pub struct Transaction<'info> {
     /// CHECK: used for merchant account PDA seed
     pub authority: SystemAccount<'info>,
 
-    // merchant account
+    // merchant account, tracks pending reward points reserved against max supply
     #[account(
+        mut,
         seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
         bump,
         constraint = merchant.authority == authority.key()
@@ -26,6 +28,12 @@ pub struct Transaction<'info> {
     )]
     pub payment_destination: Account<'info, TokenAccount>,
 
+    // "usdc" mint account, decimals used to normalize reward points
+    #[account(
+        address = USDC_MINT_PLACEHOLDER
+    )]
+    pub usdc_mint_placeholder: Account<'info, Mint>,
+
     // customer's "usdc" token account
     #[account(
         mut,
@@ -52,6 +60,47 @@ pub struct Transaction<'info> {
     )]
     pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
 
+    // init customer account tracking purchase streak and staked reward points if one does not exist
+    #[account(
+        init_if_needed,
+        seeds = [CUSTOMER_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+        payer = customer,
+        space = CustomerState::LEN
+    )]
+    pub customer_state: Box<Account<'info, CustomerState>>,
+
+    // coalition points mint, required if merchant belongs to a coalition
+    #[account(
+        mut,
+        seeds = [COALITION_POINTS_SEED.as_bytes(), merchant.coalition.as_ref()],
+        bump,
+    )]
+    pub coalition_points_mint: Option<Box<Account<'info, Mint>>>,
+
+    // merchant's coalition ledger, required if merchant belongs to a coalition
+    #[account(
+        mut,
+        seeds = [COALITION_MEMBER_SEED.as_bytes(), merchant.coalition.as_ref(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub coalition_member: Option<Box<Account<'info, CoalitionMember>>>,
+
+    // init customer's coalition points token account if one does not exist
+    #[account(
+        init_if_needed,
+        payer = customer,
+        associated_token::mint = coalition_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_coalition_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    // customer's loyalty nft token account, required for the loyalty discount
+    pub customer_nft_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    // membership account for customer's loyalty nft, required for the loyalty discount
+    pub membership: Option<Box<Account<'info, Membership>>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -67,6 +116,32 @@ pub fn transaction_handler(ctx: Context<Transaction>, amount: u64) -> Result<()>
         &[*ctx.bumps.get("reward_points_mint").unwrap()],
     ]];
 
+    // loyalty discount for customers holding a member nft, refused if membership was revoked or expired
+    let now = Clock::get()?.unix_timestamp;
+    let loyalty_discount = match (
+        &ctx.accounts.customer_nft_token_account,
+        &ctx.accounts.membership,
+    ) {
+        (Some(customer_nft_token_account), Some(membership)) => {
+            require!(
+                customer_nft_token_account.owner == ctx.accounts.customer.key()
+                    && customer_nft_token_account.mint == membership.nft_mint
+                    && customer_nft_token_account.amount == 1
+                    && membership.merchant == merchant,
+                GrizzlyError::InvalidMembership
+            );
+            require!(!membership.revoked, GrizzlyError::RevokedMembership);
+            require!(!membership.is_expired(now), GrizzlyError::MembershipExpired);
+            (amount as u128)
+                .checked_mul(ctx.accounts.merchant.loyalty_discount_basis_points as u128)
+                .unwrap()
+                .checked_div(10000)
+                .unwrap() as u64
+        }
+        _ => 0,
+    };
+    let amount_paid = amount.checked_sub(loyalty_discount).unwrap();
+
     // transfer payment from customer to merchant
     msg!("Transfer Tokens");
     let cpi_ctx = CpiContext::new(
@@ -77,27 +152,194 @@ pub fn transaction_handler(ctx: Context<Transaction>, amount: u64) -> Result<()>
             to: ctx.accounts.payment_destination.to_account_info(),
         },
     );
-    transfer(cpi_ctx, amount)?;
+    transfer(cpi_ctx, amount_paid)?;
+
+    // coalition members mint coalition points instead of merchant reward points
+    let coalition = ctx.accounts.merchant.coalition;
+    let points_decimals = if coalition == Pubkey::default() {
+        ctx.accounts.reward_points_mint.decimals
+    } else {
+        ctx.accounts
+            .coalition_points_mint
+            .as_ref()
+            .ok_or(GrizzlyError::CoalitionAccountsRequired)?
+            .decimals
+    };
+
+    // calculate reward points, normalized from "usdc" decimals to reward points decimals
+    let base_points = convert_amount(
+        amount_paid,
+        ctx.accounts.merchant.reward_points_basis_points,
+        ctx.accounts.usdc_mint_placeholder.decimals,
+        points_decimals,
+    )?;
 
-    // calculate reward points
-    let reward_amount = amount
-        .checked_mul(ctx.accounts.merchant.reward_points_basis_points as u64)
+    // update customer purchase streak, only purchases paying at least the merchant's min streak
+    // purchase count, so free checkouts can't keep a streak alive
+    let qualifying_purchase =
+        amount_paid > 0 && amount_paid >= ctx.accounts.merchant.min_streak_purchase;
+    let customer_state = &mut ctx.accounts.customer_state;
+    customer_state.merchant = merchant;
+    customer_state.customer = ctx.accounts.customer.key();
+    if qualifying_purchase {
+        customer_state.record_purchase_day(now / SECONDS_PER_DAY);
+    }
+    let current_streak = customer_state.current_streak;
+    let stake_locked = customer_state.is_stake_locked(now);
+    let stake_boost_basis_points = customer_state.stake_boost_basis_points;
+
+    // calculate streak bonus reward points, qualifying purchases only
+    let multiplier_basis_points = if qualifying_purchase {
+        ctx.accounts
+            .merchant
+            .streak_multiplier_basis_points(current_streak)
+    } else {
+        10000
+    };
+    let streak_bonus_points = (base_points as u128)
+        .checked_mul(multiplier_basis_points.saturating_sub(10000) as u128)
         .unwrap()
         .checked_div(10000)
+        .unwrap() as u64;
+
+    // calculate stake boost reward points while customer's stake is locked, at the boost accepted
+    // when staked, stake_points only accepts stakes of at least min stake amount
+    let stake_boost_points = if stake_locked {
+        (base_points as u128)
+            .checked_mul(stake_boost_basis_points as u128)
+            .unwrap()
+            .checked_div(10000)
+            .unwrap() as u64
+    } else {
+        0
+    };
+    let reward_amount = base_points
+        .checked_add(streak_bonus_points)
+        .unwrap()
+        .checked_add(stake_boost_points)
         .unwrap();
 
-    // mint reward points to customer
-    msg!("Minting Reward Points Tokens");
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        MintTo {
-            mint: ctx.accounts.reward_points_mint.to_account_info(),
-            to: ctx.accounts.customer_reward_token_account.to_account_info(),
-            authority: ctx.accounts.reward_points_mint.to_account_info(),
-        },
-        signer_seeds,
-    );
-    mint_to(cpi_ctx, reward_amount)?;
+    // merchants with a vesting period credit reward points to a pending balance instead,
+    // minted by vest_points once the refund window has passed, each checkout vests on its own
+    // pending reward points are reserved against max supply now, so vesting can't exceed it
+    let points_pending =
+        coalition == Pubkey::default() && ctx.accounts.merchant.points_vesting_period > 0;
+    if points_pending {
+        ctx.accounts.merchant.check_points_supply(
+            merchant,
+            ctx.accounts.reward_points_mint.supply,
+            reward_amount,
+        )?;
+        ctx.accounts.merchant.pending_points_supply = ctx
+            .accounts
+            .merchant
+            .pending_points_supply
+            .checked_add(reward_amount)
+            .unwrap();
+        let points_vesting_period = ctx.accounts.merchant.points_vesting_period;
+        ctx.accounts.customer_state.add_pending_points(
+            reward_amount,
+            now,
+            points_vesting_period,
+        )?;
+    } else if coalition == Pubkey::default() {
+        // check reward points max supply
+        ctx.accounts.merchant.check_points_supply(
+            merchant,
+            ctx.accounts.reward_points_mint.supply,
+            reward_amount,
+        )?;
+
+        // thaw non-transferable reward points token account
+        if ctx.accounts.merchant.non_transferable_points {
+            thaw_reward_points_account(
+                &ctx.accounts.token_program,
+                &ctx.accounts.reward_points_mint,
+                &ctx.accounts.customer_reward_token_account,
+                signer_seeds,
+            )?;
+        }
+
+        // mint reward points to customer
+        msg!("Minting Reward Points Tokens");
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.reward_points_mint.to_account_info(),
+                to: ctx.accounts.customer_reward_token_account.to_account_info(),
+                authority: ctx.accounts.reward_points_mint.to_account_info(),
+            },
+            signer_seeds,
+        );
+        mint_to(cpi_ctx, reward_amount)?;
+
+        // freeze non-transferable reward points token account
+        if ctx.accounts.merchant.non_transferable_points {
+            freeze_reward_points_account(
+                &ctx.accounts.token_program,
+                &ctx.accounts.reward_points_mint,
+                &ctx.accounts.customer_reward_token_account,
+                signer_seeds,
+            )?;
+        }
+    } else {
+        // max_points_supply caps the merchant's own reward points mint only, coalition points
+        // are a shared mint across merchants and not capped, issuance is tracked per merchant
+        // in coalition_member points_issued instead
+        let coalition_points_mint = ctx
+            .accounts
+            .coalition_points_mint
+            .as_ref()
+            .ok_or(GrizzlyError::CoalitionAccountsRequired)?;
+        let customer_coalition_token_account = ctx
+            .accounts
+            .customer_coalition_token_account
+            .as_ref()
+            .ok_or(GrizzlyError::CoalitionAccountsRequired)?;
+        let coalition_member = ctx
+            .accounts
+            .coalition_member
+            .as_mut()
+            .ok_or(GrizzlyError::CoalitionAccountsRequired)?;
+
+        // coalition points mint PDA is also mint authority
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            COALITION_POINTS_SEED.as_bytes(),
+            coalition.as_ref(),
+            &[*ctx.bumps.get("coalition_points_mint").unwrap()],
+        ]];
+
+        // mint coalition points to customer
+        msg!("Minting Coalition Points Tokens");
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: coalition_points_mint.to_account_info(),
+                to: customer_coalition_token_account.to_account_info(),
+                authority: coalition_points_mint.to_account_info(),
+            },
+            signer_seeds,
+        );
+        mint_to(cpi_ctx, reward_amount)?;
+
+        // record coalition points issued by merchant, used to settle between merchants
+        coalition_member.points_issued = coalition_member
+            .points_issued
+            .checked_add(reward_amount)
+            .unwrap();
+    }
+
+    emit!(TransactionRewarded {
+        merchant,
+        customer: ctx.accounts.customer.key(),
+        amount,
+        loyalty_discount,
+        base_points,
+        streak_bonus_points,
+        current_streak,
+        stake_boost_points,
+        points_pending,
+    });
 
     Ok(())
 }
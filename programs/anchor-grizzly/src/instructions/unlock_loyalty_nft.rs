@@ -0,0 +1,91 @@
+// thaw a soulbound loyalty NFT so it can be transferred, e.g. for wallet migration
+// signed by either the merchant authority or the NFT owner
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UnlockLoyaltyNft<'info> {
+    // merchant authority or NFT owner
+    #[account(
+        constraint = signer.key() == merchant.authority
+            || signer.key() == token_account.owner @ GrizzlyError::UnauthorizedLoyaltyNftUnlock
+    )]
+    pub signer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program, delegate of member token accounts
+    #[account(
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    // customer loyalty nft mint
+    pub nft_mint: Account<'info, Mint>,
+
+    // member token account holding frozen loyalty nft
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        constraint = token_account.is_frozen() @ GrizzlyError::LoyaltyNftNotLocked
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: loyalty nft metadata account
+    #[account(
+        address=find_metadata_account(&nft_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: loyalty nft master edition account, freeze authority of nft mint
+    #[account(
+        address=find_master_edition_account(&nft_mint.key()).0
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    // membership account for loyalty nft, only the merchant can unlock a revoked membership
+    #[account(
+        seeds = [MEMBERSHIP_SEED.as_bytes(), nft_mint.key().as_ref()],
+        bump,
+        constraint = membership.merchant == merchant.key(),
+        constraint = !membership.revoked
+            || signer.key() == merchant.authority @ GrizzlyError::RevokedMembership
+    )]
+    pub membership: Account<'info, Membership>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn unlock_loyalty_nft_handler(ctx: Context<UnlockLoyaltyNft>) -> Result<()> {
+    // PDA for signing as token account delegate
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+
+    thaw_delegated_account(CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        ThawDelegatedAccount {
+            metadata: ctx.accounts.metadata_account.to_account_info(),
+            delegate: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            token_account: ctx.accounts.token_account.to_account_info(),
+            edition: ctx.accounts.master_edition.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
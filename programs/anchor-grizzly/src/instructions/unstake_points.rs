@@ -0,0 +1,155 @@
+// unstake customer reward points, early unstake penalty is burned from the stake vault
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UnstakePoints<'info> {
+    // customer unstaking reward points
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    // customer's reward points token account
+    #[account(
+        mut,
+        associated_token::mint = reward_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    // customer's stake vault
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    // customer account tracking staked reward points
+    #[account(
+        mut,
+        seeds = [CUSTOMER_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+    )]
+    pub customer_state: Box<Account<'info, CustomerState>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn unstake_points_handler(ctx: Context<UnstakePoints>) -> Result<()> {
+    let staked_amount = ctx.accounts.customer_state.staked_amount;
+    require!(staked_amount > 0, GrizzlyError::NothingStaked);
+
+    // penalty applies if unstaked before stake unlocks, rounded up so small stakes can't avoid it
+    // penalty rate is the one accepted when the customer staked
+    let penalty =
+        if Clock::get()?.unix_timestamp < ctx.accounts.customer_state.stake_unlock_timestamp {
+            (staked_amount as u128)
+                .checked_mul(ctx.accounts.customer_state.stake_penalty_basis_points as u128)
+                .unwrap()
+                .checked_add(9999)
+                .unwrap()
+                .checked_div(10000)
+                .unwrap() as u64
+        } else {
+            0
+        };
+    let amount = staked_amount.checked_sub(penalty).unwrap();
+
+    // stake vault PDA is its own authority
+    let merchant = ctx.accounts.merchant.key();
+    let customer = ctx.accounts.customer.key();
+    let vault_signer_seeds: &[&[&[u8]]] = &[&[
+        STAKE_VAULT_SEED.as_bytes(),
+        merchant.as_ref(),
+        customer.as_ref(),
+        &[*ctx.bumps.get("stake_vault").unwrap()],
+    ]];
+
+    // burn early unstake penalty
+    if penalty > 0 {
+        msg!("Burning Reward Points Tokens");
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.reward_points_mint.to_account_info(),
+                from: ctx.accounts.stake_vault.to_account_info(),
+                authority: ctx.accounts.stake_vault.to_account_info(),
+            },
+            vault_signer_seeds,
+        );
+        burn(cpi_ctx, penalty)?;
+    }
+
+    // reward points mint PDA is also freeze authority
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    // transfer remaining reward points from stake vault to customer
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.stake_vault.to_account_info(),
+            to: ctx.accounts.customer_reward_token_account.to_account_info(),
+        },
+        vault_signer_seeds,
+    );
+    transfer(cpi_ctx, amount)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    ctx.accounts.customer_state.staked_amount = 0;
+    ctx.accounts.customer_state.stake_unlock_timestamp = 0;
+    ctx.accounts.customer_state.stake_boost_basis_points = 0;
+    ctx.accounts.customer_state.stake_penalty_basis_points = 0;
+
+    emit!(PointsUnstaked {
+        merchant,
+        customer,
+        amount,
+        penalty,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+// update cash back rate and min redemption for redeeming reward points for "usdc"
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateCashBack<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_cash_back_handler(
+    ctx: Context<UpdateCashBack>,
+    cash_back_basis_points: u16,
+    min_redemption_points: u64,
+) -> Result<()> {
+    require!(
+        cash_back_basis_points <= 10000,
+        GrizzlyError::InvalidBasisPoints
+    );
+    ctx.accounts.merchant.cash_back_basis_points = cash_back_basis_points;
+    ctx.accounts.merchant.min_redemption_points = min_redemption_points;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// update collection nft metadata and creators, loyalty collection mint PDA is update authority
+// co-creators are verified by signing the transaction (passed as remaining accounts)
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateCollectionMetadata<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program
+    #[account(
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    /// CHECK: collection metadata account
+    #[account(
+        mut,
+        address=find_metadata_account(&loyalty_collection_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn update_collection_metadata_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateCollectionMetadata<'info>>,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Vec<CollectionCreator>,
+) -> Result<()> {
+    validate_royalties(
+        seller_fee_basis_points,
+        &creators,
+        ctx.accounts.merchant.key(),
+    )?;
+
+    // creators already verified stay verified, new creators are verified by sign_metadata below
+    let data = loyalty_nft_data(&ctx.accounts.metadata_account)?;
+    let verified_creators = data.creators.clone().unwrap_or_default();
+    let creators: Vec<Creator> = creators
+        .iter()
+        .map(|creator| Creator {
+            address: creator.address,
+            verified: verified_creators
+                .iter()
+                .any(|c| c.address == creator.address && c.verified),
+            share: creator.share,
+        })
+        .collect();
+
+    // PDA for signing
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata_account.to_account_info(),
+                update_authority: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+        Some(DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points,
+            creators: Some(creators.clone()),
+            ..data
+        }),
+        None,
+        None,
+    )?;
+
+    // verify unverified creators signing the transaction
+    let signers = std::iter::once(ctx.accounts.authority.to_account_info())
+        .chain(ctx.remaining_accounts.iter().cloned())
+        .filter(|account| account.is_signer);
+    for signer in signers {
+        if creators
+            .iter()
+            .any(|creator| creator.address == signer.key() && !creator.verified)
+        {
+            sign_metadata(CpiContext::new(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                SignMetadata {
+                    creator: signer,
+                    metadata: ctx.accounts.metadata_account.to_account_info(),
+                },
+            ))?;
+        }
+    }
+    Ok(())
+}
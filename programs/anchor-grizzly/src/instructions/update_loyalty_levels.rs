@@ -0,0 +1,55 @@
+// set loyalty NFT level table, customers burn reward points to upgrade their NFT to the next level
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateLoyaltyLevels<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // init loyalty level table if one does not exist
+    #[account(
+        init_if_needed,
+        seeds = [LOYALTY_LEVELS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        payer = authority,
+        space = LoyaltyLevels::LEN
+    )]
+    pub loyalty_levels: Account<'info, LoyaltyLevels>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_loyalty_levels_handler(
+    ctx: Context<UpdateLoyaltyLevels>,
+    levels: Vec<LoyaltyLevel>,
+) -> Result<()> {
+    require!(
+        levels.len() <= MAX_LOYALTY_LEVELS,
+        GrizzlyError::TooManyLoyaltyLevels
+    );
+    for level in levels.iter() {
+        require!(
+            !level.uri.is_empty() && level.uri.len() <= MAX_URI_LENGTH,
+            GrizzlyError::InvalidLoyaltyLevels
+        );
+    }
+
+    // levels can be added or updated but not removed, upgraded members keep their level
+    let loyalty_levels = &mut ctx.accounts.loyalty_levels;
+    require!(
+        levels.len() >= loyalty_levels.levels.len(),
+        GrizzlyError::LoyaltyLevelsRemoved
+    );
+    loyalty_levels.merchant = ctx.accounts.merchant.key();
+    loyalty_levels.levels = levels;
+    Ok(())
+}
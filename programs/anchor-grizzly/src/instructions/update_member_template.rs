@@ -0,0 +1,50 @@
+// set member nft metadata template, member nfts get a generated name and uri with their member number
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateMemberTemplate<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // init member template if one does not exist
+    #[account(
+        init_if_needed,
+        seeds = [MEMBER_TEMPLATE_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        payer = authority,
+        space = MemberTemplate::LEN
+    )]
+    pub member_template: Account<'info, MemberTemplate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_member_template_handler(
+    ctx: Context<UpdateMemberTemplate>,
+    base_uri: String,
+    name_prefix: String,
+    symbol: String,
+) -> Result<()> {
+    require!(
+        base_uri.len() <= MemberTemplate::MAX_BASE_URI_LENGTH
+            && name_prefix.len() <= MemberTemplate::MAX_NAME_PREFIX_LENGTH
+            && symbol.len() <= MAX_SYMBOL_LENGTH,
+        GrizzlyError::InvalidMemberTemplate
+    );
+
+    let member_template = &mut ctx.accounts.member_template;
+    member_template.merchant = ctx.accounts.merchant.key();
+    member_template.base_uri = base_uri;
+    member_template.name_prefix = name_prefix;
+    member_template.symbol = symbol;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+// update loyalty collection max members (0 = no cap) and "usdc" price to mint a member nft
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateMembershipConfig<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_membership_config_handler(
+    ctx: Context<UpdateMembershipConfig>,
+    max_members: u64,
+    member_mint_price: u64,
+) -> Result<()> {
+    ctx.accounts.merchant.max_members = max_members;
+    ctx.accounts.merchant.member_mint_price = member_mint_price;
+    Ok(())
+}
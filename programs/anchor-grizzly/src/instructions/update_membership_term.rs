@@ -0,0 +1,32 @@
+// update membership duration (seconds, 0 = never expires) and "usdc" price to renew a membership
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateMembershipTerm<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_membership_term_handler(
+    ctx: Context<UpdateMembershipTerm>,
+    membership_duration: i64,
+    renewal_price: u64,
+) -> Result<()> {
+    require!(
+        membership_duration >= 0,
+        GrizzlyError::InvalidMembershipDuration
+    );
+    ctx.accounts.merchant.membership_duration = membership_duration;
+    ctx.accounts.merchant.renewal_price = renewal_price;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+// update perk uses on newly minted member nfts (e.g. 10 free coffees), 0 = no perks
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdatePerks<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_perks_handler(ctx: Context<UpdatePerks>, perk_uses: u64) -> Result<()> {
+    // token metadata requires at least 2 total uses for UseMethod::Multiple
+    require!(
+        perk_uses == 0 || perk_uses >= 2,
+        GrizzlyError::InvalidPerkUses
+    );
+    ctx.accounts.merchant.perk_uses = perk_uses;
+    ctx.accounts.merchant.check_programmable_loyalty_nft()
+}
@@ -0,0 +1,35 @@
+// update points vesting period, checkout reward points stay pending until the refund window passes
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdatePointsVesting<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_points_vesting_handler(
+    ctx: Context<UpdatePointsVesting>,
+    points_vesting_period: i64,
+) -> Result<()> {
+    require!(
+        points_vesting_period >= 0,
+        GrizzlyError::InvalidVestingPeriod
+    );
+    // coalition merchants mint coalition points at checkout, which don't vest
+    require!(
+        points_vesting_period == 0 || ctx.accounts.merchant.coalition == Pubkey::default(),
+        GrizzlyError::VestingUnavailableForCoalition
+    );
+    ctx.accounts.merchant.points_vesting_period = points_vesting_period;
+    Ok(())
+}
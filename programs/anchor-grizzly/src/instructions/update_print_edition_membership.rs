@@ -0,0 +1,30 @@
+// update print edition membership setting, member nfts are minted as print editions of the member master nft
+// print edition member nfts are immutable, so loyalty levels and expiry don't show on them:
+// upgrade_loyalty_nft and refresh_membership_metadata reject them and they can't be reissued,
+// their membership expiry still applies at checkout and renewal, members minted before
+// print edition membership was turned on are unaffected
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdatePrintEditionMembership<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_print_edition_membership_handler(
+    ctx: Context<UpdatePrintEditionMembership>,
+    print_edition_membership: bool,
+) -> Result<()> {
+    ctx.accounts.merchant.print_edition_membership = print_edition_membership;
+    ctx.accounts.merchant.check_programmable_loyalty_nft()
+}
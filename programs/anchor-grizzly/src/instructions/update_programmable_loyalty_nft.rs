@@ -0,0 +1,31 @@
+// update programmable member nft setting, member nfts are minted as token metadata programmable nfts
+// the rule set restricts member nft transfers (e.g. to an allowlist), default pubkey = no rule set
+// member nfts keep the rule set they were minted with, members minted before are unaffected
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateProgrammableLoyaltyNft<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_programmable_loyalty_nft_handler(
+    ctx: Context<UpdateProgrammableLoyaltyNft>,
+    programmable_loyalty_nft: bool,
+    rule_set: Pubkey,
+) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.programmable_loyalty_nft = programmable_loyalty_nft;
+    merchant.loyalty_nft_rule_set = rule_set;
+    merchant.check_programmable_loyalty_nft()
+}
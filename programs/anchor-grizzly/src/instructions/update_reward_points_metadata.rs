@@ -0,0 +1,75 @@
+// update reward points mint metadata (name, symbol, uri), e.g. when merchant rebrands
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateRewardPointsMetadata<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint, also metadata update authority
+    #[account(
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    /// CHECK: reward points mint metadata account, updated via CPI to token-metadata program
+    #[account(
+        mut,
+        address=find_metadata_account(&reward_points_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn update_reward_points_metadata_handler(
+    ctx: Context<UpdateRewardPointsMetadata>,
+    uri: String,
+    name: String,
+    symbol: String,
+) -> Result<()> {
+    // reward points mint PDA used to sign as metadata update authority
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // update metadata account for reward points mint
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata_account.to_account_info(),
+                update_authority: ctx.accounts.reward_points_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+        Some(DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }),
+        None,
+        None,
+    )?;
+
+    Ok(())
+}
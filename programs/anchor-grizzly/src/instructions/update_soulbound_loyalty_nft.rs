@@ -0,0 +1,26 @@
+// update soulbound setting, soulbound loyalty NFTs are frozen in the member's token account after mint
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateSoulboundLoyaltyNft<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_soulbound_loyalty_nft_handler(
+    ctx: Context<UpdateSoulboundLoyaltyNft>,
+    soulbound: bool,
+) -> Result<()> {
+    ctx.accounts.merchant.soulbound_loyalty_nft = soulbound;
+    ctx.accounts.merchant.check_programmable_loyalty_nft()
+}
@@ -0,0 +1,35 @@
+// update reward points staking, boost applied at checkout while staked and early unstake penalty
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateStaking<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_staking_handler(
+    ctx: Context<UpdateStaking>,
+    stake_boost_basis_points: u16,
+    early_unstake_penalty_basis_points: u16,
+    min_stake_amount: u64,
+) -> Result<()> {
+    require!(
+        stake_boost_basis_points <= 10000 && early_unstake_penalty_basis_points <= 10000,
+        GrizzlyError::InvalidBasisPoints
+    );
+    ctx.accounts.merchant.stake_boost_basis_points = stake_boost_basis_points;
+    ctx.accounts.merchant.early_unstake_penalty_basis_points = early_unstake_penalty_basis_points;
+    // stakes below min stake amount are rejected, and earn no boost if the min is raised later
+    ctx.accounts.merchant.min_stake_amount = min_stake_amount;
+    Ok(())
+}
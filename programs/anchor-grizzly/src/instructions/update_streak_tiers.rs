@@ -0,0 +1,48 @@
+// update purchase streak bonus tiers, reward points multiplier for consecutive purchase days
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateStreakTiers<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_streak_tiers_handler(
+    ctx: Context<UpdateStreakTiers>,
+    streak_tiers: Vec<StreakTier>,
+    min_streak_purchase: u64,
+) -> Result<()> {
+    require!(
+        streak_tiers.len() <= MAX_STREAK_TIERS,
+        GrizzlyError::TooManyStreakTiers
+    );
+
+    // tiers sorted by min streak days, each tier at least 1x multiplier
+    let mut previous_min_streak_days = 0;
+    for tier in streak_tiers.iter() {
+        require!(
+            tier.min_streak_days > previous_min_streak_days
+                && tier.multiplier_basis_points >= 10000,
+            GrizzlyError::InvalidStreakTiers
+        );
+        previous_min_streak_days = tier.min_streak_days;
+    }
+
+    // unused tiers are left zeroed
+    let mut tiers = [StreakTier::default(); MAX_STREAK_TIERS];
+    tiers[..streak_tiers.len()].copy_from_slice(&streak_tiers);
+    ctx.accounts.merchant.streak_tiers = tiers;
+    // min "usdc" amount paid (after loyalty discount) for a purchase day to count, free checkouts never count
+    ctx.accounts.merchant.min_streak_purchase = min_streak_purchase;
+    Ok(())
+}
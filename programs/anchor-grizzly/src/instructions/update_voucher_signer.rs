@@ -0,0 +1,26 @@
+// update voucher signer, off-chain key allowed to sign reward points vouchers
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpdateVoucherSigner<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+}
+
+pub fn update_voucher_signer_handler(
+    ctx: Context<UpdateVoucherSigner>,
+    voucher_signer: Pubkey,
+) -> Result<()> {
+    ctx.accounts.merchant.voucher_signer = voucher_signer;
+    Ok(())
+}
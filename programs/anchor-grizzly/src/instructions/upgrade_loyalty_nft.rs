@@ -0,0 +1,212 @@
+// burn reward points to upgrade customer's loyalty NFT to the next level in the merchant's level table
+// loyalty collection mint PDA is update authority of customer loyalty NFTs
+use crate::*;
+
+#[derive(Accounts)]
+pub struct UpgradeLoyaltyNft<'info> {
+    // customer upgrading loyalty NFT
+    pub customer: Signer<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    // customer's reward points token account
+    #[account(
+        mut,
+        associated_token::mint = reward_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    // collection nft for merchant loyalty program, update authority of customer loyalty NFT
+    // pays for programmable member nft updates
+    #[account(
+        mut,
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    // customer loyalty nft mint
+    pub customer_nft_mint: Account<'info, Mint>,
+
+    // customer token account holding loyalty nft
+    #[account(
+        associated_token::mint = customer_nft_mint,
+        associated_token::authority = customer,
+        constraint = customer_nft_token_account.amount == 1
+    )]
+    pub customer_nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: customer loyalty nft metadata account
+    #[account(
+        mut,
+        address=find_metadata_account(&customer_nft_mint.key()).0
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    // merchant's loyalty level table
+    #[account(
+        seeds = [LOYALTY_LEVELS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub loyalty_levels: Account<'info, LoyaltyLevels>,
+
+    // membership account, tracks loyalty NFT level
+    #[account(
+        mut,
+        seeds = [MEMBERSHIP_SEED.as_bytes(), customer_nft_mint.key().as_ref()],
+        bump,
+        constraint = membership.merchant == merchant.key(),
+        constraint = !membership.revoked @ GrizzlyError::RevokedMembership
+    )]
+    pub membership: Account<'info, Membership>,
+
+    /// CHECK: instructions sysvar, required for programmable member nfts
+    #[account(address = sysvar_instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token auth rules program, required if the member nft has a rule set
+    #[account(address = TOKEN_AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: rule set the member nft was minted with, checked against the nft's metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    // required for programmable member nfts
+    pub system_program: Option<Program<'info, System>>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn upgrade_loyalty_nft_handler(ctx: Context<UpgradeLoyaltyNft>) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .membership
+            .is_expired(Clock::get()?.unix_timestamp),
+        GrizzlyError::MembershipExpired
+    );
+    require_mutable_loyalty_nft(&ctx.accounts.metadata_account)?;
+
+    let current_level = ctx.accounts.membership.level as usize;
+    let next_level = ctx
+        .accounts
+        .loyalty_levels
+        .levels
+        .get(current_level)
+        .ok_or(GrizzlyError::MaxLoyaltyLevelReached)?
+        .clone();
+
+    let merchant = ctx.accounts.merchant.key();
+
+    // reward points mint PDA is also freeze authority
+    let points_signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            points_signer_seeds,
+        )?;
+    }
+
+    // burn customer's reward points for upgrade price
+    msg!("Burning Reward Points Tokens");
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.reward_points_mint.to_account_info(),
+            from: ctx.accounts.customer_reward_token_account.to_account_info(),
+            authority: ctx.accounts.customer.to_account_info(),
+        },
+    );
+    burn(cpi_ctx, next_level.upgrade_price)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            points_signer_seeds,
+        )?;
+    }
+
+    // PDA for signing as loyalty NFT update authority
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LOYALTY_NFT_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("loyalty_collection_mint").unwrap()],
+    ]];
+
+    // keep existing metadata, only move uri to the next level
+    let mut data = loyalty_nft_data(&ctx.accounts.metadata_account)?;
+    data.uri = next_level.uri;
+
+    update_loyalty_nft_data(
+        UpdateLoyaltyNft {
+            loyalty_collection_mint: ctx.accounts.loyalty_collection_mint.to_account_info(),
+            nft_mint: ctx.accounts.customer_nft_mint.to_account_info(),
+            metadata_account: ctx.accounts.metadata_account.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx
+                .accounts
+                .system_program
+                .as_ref()
+                .map(|program| program.to_account_info()),
+            sysvar_instructions: ctx
+                .accounts
+                .sysvar_instructions
+                .as_ref()
+                .map(|account| account.to_account_info()),
+            authorization_rules_program: ctx
+                .accounts
+                .authorization_rules_program
+                .as_ref()
+                .map(|program| program.to_account_info()),
+            authorization_rules: ctx
+                .accounts
+                .authorization_rules
+                .as_ref()
+                .map(|account| account.to_account_info()),
+        },
+        data,
+        signer_seeds,
+    )?;
+
+    let membership = &mut ctx.accounts.membership;
+    membership.level = membership.level.checked_add(1).unwrap();
+
+    emit!(LoyaltyNftUpgraded {
+        merchant,
+        customer: ctx.accounts.customer.key(),
+        nft_mint: ctx.accounts.customer_nft_mint.key(),
+        level: membership.level,
+        points: next_level.upgrade_price,
+    });
+    Ok(())
+}
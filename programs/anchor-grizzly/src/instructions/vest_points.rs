@@ -0,0 +1,124 @@
+// mint customer's pending reward points vested so far, permissionless so anyone can crank it
+use crate::*;
+
+#[derive(Accounts)]
+pub struct VestPoints<'info> {
+    // anyone can vest pending reward points, pays for customer token account if needed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // customer receiving vested reward points
+    pub customer: SystemAccount<'info>,
+
+    /// CHECK: used for merchant account PDA seed
+    pub authority: SystemAccount<'info>,
+
+    // merchant account, releases vested points from pending reward points supply
+    #[account(
+        mut,
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's reward points mint
+    #[account(
+        mut,
+        seeds = [REWARD_POINTS_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.reward_points_mint,
+    )]
+    pub reward_points_mint: Account<'info, Mint>,
+
+    // init customer's reward points token account if one does not exist
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = reward_points_mint,
+        associated_token::authority = customer
+    )]
+    pub customer_reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    // customer account tracking pending reward points
+    #[account(
+        mut,
+        seeds = [CUSTOMER_SEED.as_bytes(), merchant.key().as_ref(), customer.key().as_ref()],
+        bump,
+    )]
+    pub customer_state: Box<Account<'info, CustomerState>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn vest_points_handler(ctx: Context<VestPoints>) -> Result<()> {
+    require!(
+        ctx.accounts.customer_state.pending_points > 0,
+        GrizzlyError::NoPendingPoints
+    );
+    let vested_points = ctx
+        .accounts
+        .customer_state
+        .take_vested_points(Clock::get()?.unix_timestamp);
+    require!(vested_points > 0, GrizzlyError::PointsNotVested);
+
+    // vested points were checked against max supply at checkout, release the reservation
+    ctx.accounts.merchant.pending_points_supply = ctx
+        .accounts
+        .merchant
+        .pending_points_supply
+        .checked_sub(vested_points)
+        .unwrap();
+
+    // reward points mint PDA is also mint authority
+    let merchant = ctx.accounts.merchant.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        REWARD_POINTS_SEED.as_bytes(),
+        merchant.as_ref(),
+        &[*ctx.bumps.get("reward_points_mint").unwrap()],
+    ]];
+
+    // thaw non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        thaw_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    // mint vested reward points to customer
+    msg!("Minting Reward Points Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.reward_points_mint.to_account_info(),
+            to: ctx.accounts.customer_reward_token_account.to_account_info(),
+            authority: ctx.accounts.reward_points_mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    mint_to(cpi_ctx, vested_points)?;
+
+    // freeze non-transferable reward points token account
+    if ctx.accounts.merchant.non_transferable_points {
+        freeze_reward_points_account(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_points_mint,
+            &ctx.accounts.customer_reward_token_account,
+            signer_seeds,
+        )?;
+    }
+
+    emit!(PointsVested {
+        merchant,
+        customer: ctx.accounts.customer.key(),
+        points: vested_points,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// move collection nft out of the merchant's collection vault, signed by merchant authority
+// the collection nft is only held in the vault so it can't be transferred or burned by accident
+use crate::*;
+
+#[derive(Accounts)]
+pub struct WithdrawCollectionNft<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account, owner of collection vault
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // collection nft for merchant loyalty program
+    #[account(
+        seeds = [LOYALTY_NFT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+        address = merchant.loyalty_collection_mint
+    )]
+    pub loyalty_collection_mint: Account<'info, Mint>,
+
+    // merchant's collection nft vault
+    #[account(
+        mut,
+        seeds = [COLLECTION_VAULT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub collection_vault: Account<'info, TokenAccount>,
+
+    // token account receiving the collection nft
+    #[account(
+        mut,
+        token::mint = loyalty_collection_mint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_collection_nft_handler(ctx: Context<WithdrawCollectionNft>) -> Result<()> {
+    // merchant PDA signs as collection vault owner
+    let authority = ctx.accounts.authority.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
+
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.collection_vault.to_account_info(),
+            authority: ctx.accounts.merchant.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer(cpi_ctx, 1)?;
+    Ok(())
+}
@@ -0,0 +1,58 @@
+// withdraw "usdc" from merchant's points vault back to payment destination, signed by merchant authority
+use crate::*;
+
+#[derive(Accounts)]
+pub struct WithdrawPointsVault<'info> {
+    // authority of merchant account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // merchant account, owner of points vault
+    #[account(
+        seeds = [MERCHANT_SEED.as_bytes(), authority.key().as_ref()],
+        bump,
+        constraint = merchant.authority == authority.key()
+    )]
+    pub merchant: Account<'info, MerchantState>,
+
+    // merchant's payment destination, receives vault funds
+    #[account(
+        mut,
+        token::mint = USDC_MINT_PLACEHOLDER,
+        address = merchant.payment_destination,
+    )]
+    pub payment_destination: Account<'info, TokenAccount>,
+
+    // merchant's "usdc" points vault
+    #[account(
+        mut,
+        seeds = [POINTS_VAULT_SEED.as_bytes(), merchant.key().as_ref()],
+        bump,
+    )]
+    pub points_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_points_vault_handler(ctx: Context<WithdrawPointsVault>, amount: u64) -> Result<()> {
+    // merchant PDA signs as points vault owner
+    let authority = ctx.accounts.authority.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        MERCHANT_SEED.as_bytes(),
+        authority.as_ref(),
+        &[*ctx.bumps.get("merchant").unwrap()],
+    ]];
+
+    msg!("Transfer Tokens");
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.points_vault.to_account_info(),
+            authority: ctx.accounts.merchant.to_account_info(),
+            to: ctx.accounts.payment_destination.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer(cpi_ctx, amount)?;
+    Ok(())
+}
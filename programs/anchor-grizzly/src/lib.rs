@@ -1,34 +1,93 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{pubkey, pubkey::Pubkey},
+    solana_program::{
+        ed25519_program,
+        instruction::{AccountMeta, Instruction},
+        program::{invoke, invoke_signed},
+        program_option::COption,
+        pubkey,
+        pubkey::Pubkey,
+        sysvar::instructions as sysvar_instructions,
+    },
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
     metadata::{
-        create_master_edition_v3, create_metadata_accounts_v3,
-        set_and_verify_sized_collection_item, sign_metadata, CreateMasterEditionV3,
-        CreateMetadataAccountsV3, Metadata, SetAndVerifySizedCollectionItem, SignMetadata,
+        create_master_edition_v3, create_metadata_accounts_v3, freeze_delegated_account,
+        mint_new_edition_from_master_edition_via_token, set_and_verify_sized_collection_item,
+        sign_metadata, thaw_delegated_account, update_metadata_accounts_v2, CreateMasterEditionV3,
+        CreateMetadataAccountsV3, FreezeDelegatedAccount, Metadata,
+        MintNewEditionFromMasterEditionViaToken, SetAndVerifySizedCollectionItem, SignMetadata,
+        ThawDelegatedAccount, UpdateMetadataAccountsV2,
+    },
+    token::{
+        approve, burn, freeze_account, initialize_account3, initialize_mint2, mint_to,
+        thaw_account, transfer, Approve, Burn, FreezeAccount, InitializeAccount3, InitializeMint2,
+        Mint, MintTo, ThawAccount, Token, TokenAccount, Transfer,
     },
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
 };
 use mpl_token_metadata::{
-    pda::{find_master_edition_account, find_metadata_account},
-    state::{CollectionDetails, Creator, DataV2},
+    instruction::{
+        approve_use_authority,
+        builders::{CreateBuilder, MintBuilder, UpdateBuilder},
+        unverify_sized_collection_item, utilize, CollectionDetailsToggle, CollectionToggle,
+        CreateArgs, InstructionBuilder, MintArgs, RuleSetToggle, UpdateArgs, UsesToggle,
+    },
+    pda::{
+        find_master_edition_account, find_metadata_account, find_program_as_burner_account,
+        find_token_record_account, find_use_authority_account,
+    },
+    state::{
+        AssetData, Collection, CollectionDetails, Creator, Data, DataV2, Metadata as MetadataState,
+        PrintSupply, ProgrammableConfig, TokenMetadataAccount, TokenStandard, UseMethod, Uses,
+        MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+    },
 };
 
+mod error;
+use error::*;
+mod events;
+use events::*;
 mod instructions;
 use instructions::*;
 mod state;
 use state::*;
+mod utils;
+use utils::*;
 
 declare_id!("4m2iCzvckHmiXf4bV4xHckVAE2tMNLt2GgUziSr7uTiF");
 
 #[constant]
 pub const USDC_MINT_PLACEHOLDER: Pubkey = pubkey!("Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr");
 // pub const USDC_MINT_PLACEHOLDER: Pubkey = pubkey!("1oveQg3XfAfY2Rw1SpwvTe5tVnaphWRXiNB9pcZE96c");
+// token auth rules program, owner of the rule sets restricting programmable member nft transfers
+pub const TOKEN_AUTH_RULES_PROGRAM_ID: Pubkey =
+    pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 pub const MERCHANT_SEED: &str = "MERCHANT";
 pub const REWARD_POINTS_SEED: &str = "REWARD_POINTS";
 pub const LOYALTY_NFT_SEED: &str = "LOYALTY_NFT";
+pub const VOUCHER_SEED: &str = "VOUCHER";
+pub const CUSTOMER_SEED: &str = "CUSTOMER";
+pub const POINTS_VAULT_SEED: &str = "POINTS_VAULT";
+pub const COALITION_SEED: &str = "COALITION";
+pub const COALITION_POINTS_SEED: &str = "COALITION_POINTS";
+pub const COALITION_MEMBER_SEED: &str = "COALITION_MEMBER";
+pub const EXCHANGE_RATE_SEED: &str = "EXCHANGE_RATE";
+pub const STAKE_VAULT_SEED: &str = "STAKE_VAULT";
+pub const LOYALTY_LEVELS_SEED: &str = "LOYALTY_LEVELS";
+pub const MEMBERSHIP_SEED: &str = "MEMBERSHIP";
+pub const MEMBER_TEMPLATE_SEED: &str = "MEMBER_TEMPLATE";
+pub const MEMBER_MASTER_SEED: &str = "MEMBER_MASTER";
+pub const COLLECTION_VAULT_SEED: &str = "COLLECTION_VAULT";
+pub const MAX_STREAK_TIERS: usize = 4;
+pub const MAX_LOYALTY_LEVELS: usize = 5;
+pub const MAX_PENDING_BUCKETS: usize = 8;
+pub const SECONDS_PER_DAY: i64 = 86400;
+// max reward points and coalition points mint decimals, keeps convert_amount scaling within u128
+pub const MAX_POINTS_DECIMALS: u8 = 9;
+
+// reward points supply thresholds (basis points of max supply) that emit a PointsSupplyWarning
+pub const POINTS_SUPPLY_WARNING_THRESHOLDS: [u16; 3] = [5000, 7500, 9000];
 
 #[program]
 pub mod anchor_grizzly {
@@ -40,14 +99,27 @@ pub mod anchor_grizzly {
     }
 
     // init reward points mint
+    #[allow(clippy::too_many_arguments)]
     pub fn init_reward_points(
         ctx: Context<InitRewardPoints>,
         reward_points_basis_points: u16,
+        decimals: u8,
+        max_points_supply: u64,
+        non_transferable: bool,
         uri: String,
         name: String,
         symbol: String,
     ) -> Result<()> {
-        instructions::init_reward_points_handler(ctx, reward_points_basis_points, uri, name, symbol)
+        instructions::init_reward_points_handler(
+            ctx,
+            reward_points_basis_points,
+            decimals,
+            max_points_supply,
+            non_transferable,
+            uri,
+            name,
+            symbol,
+        )
     }
 
     // transfer usdc tokens from customer to merchant, mint reward points to customer
@@ -62,6 +134,8 @@ pub mod anchor_grizzly {
         uri: String,
         name: String,
         symbol: String,
+        seller_fee_basis_points: u16,
+        creators: Vec<CollectionCreator>,
     ) -> Result<()> {
         instructions::create_collection_nft_handler(
             ctx,
@@ -69,17 +143,14 @@ pub mod anchor_grizzly {
             uri,
             name,
             symbol,
+            seller_fee_basis_points,
+            creators,
         )
     }
 
     // create NFT in collection
-    pub fn create_nft_in_collection(
-        ctx: Context<CreateNftInCollection>,
-        uri: String,
-        name: String,
-        symbol: String,
-    ) -> Result<()> {
-        instructions::create_nft_in_collection_handler(ctx, uri, name, symbol)
+    pub fn create_nft_in_collection(ctx: Context<CreateNftInCollection>) -> Result<()> {
+        instructions::create_nft_in_collection_handler(ctx)
     }
 
     // update reward points reward basis points (% minted at checkout)
@@ -102,4 +173,294 @@ pub mod anchor_grizzly {
     pub fn mint_reward_points(ctx: Context<MintRewardPoints>, amount: u64) -> Result<()> {
         instructions::mint_reward_points_handler(ctx, amount)
     }
+
+    // update voucher signer, off-chain key (e.g. POS backend) allowed to sign reward points vouchers
+    pub fn update_voucher_signer(
+        ctx: Context<UpdateVoucherSigner>,
+        voucher_signer: Pubkey,
+    ) -> Result<()> {
+        instructions::update_voucher_signer_handler(ctx, voucher_signer)
+    }
+
+    // mint reward points to customer for a voucher signed by merchant's voucher signer
+    pub fn redeem_voucher(
+        ctx: Context<RedeemVoucher>,
+        points: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::redeem_voucher_handler(ctx, points, nonce, expiry)
+    }
+
+    // raise reward points max supply (merchant liability budget)
+    pub fn raise_max_points_supply(
+        ctx: Context<RaiseMaxPointsSupply>,
+        max_points_supply: u64,
+    ) -> Result<()> {
+        instructions::raise_max_points_supply_handler(ctx, max_points_supply)
+    }
+
+    // update reward points mint metadata (name, symbol, uri)
+    pub fn update_reward_points_metadata(
+        ctx: Context<UpdateRewardPointsMetadata>,
+        uri: String,
+        name: String,
+        symbol: String,
+    ) -> Result<()> {
+        instructions::update_reward_points_metadata_handler(ctx, uri, name, symbol)
+    }
+
+    // update purchase streak bonus tiers (reward points multiplier for consecutive purchase days)
+    // and min "usdc" amount paid for a purchase to count towards the streak
+    pub fn update_streak_tiers(
+        ctx: Context<UpdateStreakTiers>,
+        streak_tiers: Vec<StreakTier>,
+        min_streak_purchase: u64,
+    ) -> Result<()> {
+        instructions::update_streak_tiers_handler(ctx, streak_tiers, min_streak_purchase)
+    }
+
+    // update cash back rate (basis points of reward points value paid in "usdc") and min redemption
+    pub fn update_cash_back(
+        ctx: Context<UpdateCashBack>,
+        cash_back_basis_points: u16,
+        min_redemption_points: u64,
+    ) -> Result<()> {
+        instructions::update_cash_back_handler(ctx, cash_back_basis_points, min_redemption_points)
+    }
+
+    // transfer "usdc" from merchant to points vault, backs reward points cash back
+    pub fn fund_points_vault(ctx: Context<FundPointsVault>, amount: u64) -> Result<()> {
+        instructions::fund_points_vault_handler(ctx, amount)
+    }
+
+    // transfer "usdc" from points vault back to merchant's payment destination
+    pub fn withdraw_points_vault(ctx: Context<WithdrawPointsVault>, amount: u64) -> Result<()> {
+        instructions::withdraw_points_vault_handler(ctx, amount)
+    }
+
+    // burn customer reward points, pay out "usdc" from points vault at merchant's cash back rate
+    pub fn redeem_points_for_cash(ctx: Context<RedeemPointsForCash>, points: u64) -> Result<()> {
+        instructions::redeem_points_for_cash_handler(ctx, points)
+    }
+
+    // init coalition and shared coalition points mint
+    pub fn init_coalition(ctx: Context<InitCoalition>, decimals: u8) -> Result<()> {
+        instructions::init_coalition_handler(ctx, decimals)
+    }
+
+    // admit merchant to coalition, signed by coalition authority and merchant authority
+    pub fn admit_coalition_merchant(ctx: Context<AdmitCoalitionMerchant>) -> Result<()> {
+        instructions::admit_coalition_merchant_handler(ctx)
+    }
+
+    // burn customer coalition points redeemed at a member merchant
+    pub fn redeem_coalition_points(ctx: Context<RedeemCoalitionPoints>, points: u64) -> Result<()> {
+        instructions::redeem_coalition_points_handler(ctx, points)
+    }
+
+    // publish rate (basis points) and daily volume cap for accepting another merchant's reward points
+    // rate 0 disables a published exchange rate
+    pub fn set_exchange_rate(
+        ctx: Context<SetExchangeRate>,
+        rate_basis_points: u16,
+        daily_volume_cap: u64,
+    ) -> Result<()> {
+        instructions::set_exchange_rate_handler(ctx, rate_basis_points, daily_volume_cap)
+    }
+
+    // burn customer's reward points from source merchant, mint destination merchant's reward points
+    pub fn swap_points(ctx: Context<SwapPoints>, amount: u64) -> Result<()> {
+        instructions::swap_points_handler(ctx, amount)
+    }
+
+    // update stake boost (% extra reward points at checkout while staked), early unstake penalty
+    // and min staked reward points for the stake boost
+    pub fn update_staking(
+        ctx: Context<UpdateStaking>,
+        stake_boost_basis_points: u16,
+        early_unstake_penalty_basis_points: u16,
+        min_stake_amount: u64,
+    ) -> Result<()> {
+        instructions::update_staking_handler(
+            ctx,
+            stake_boost_basis_points,
+            early_unstake_penalty_basis_points,
+            min_stake_amount,
+        )
+    }
+
+    // lock customer reward points in stake vault for `lock_duration` seconds
+    pub fn stake_points(ctx: Context<StakePoints>, amount: u64, lock_duration: i64) -> Result<()> {
+        instructions::stake_points_handler(ctx, amount, lock_duration)
+    }
+
+    // return staked reward points to customer, penalty burned if unstaked before unlock
+    pub fn unstake_points(ctx: Context<UnstakePoints>) -> Result<()> {
+        instructions::unstake_points_handler(ctx)
+    }
+
+    // update points vesting period (seconds), checkout reward points stay pending until vested
+    pub fn update_points_vesting(
+        ctx: Context<UpdatePointsVesting>,
+        points_vesting_period: i64,
+    ) -> Result<()> {
+        instructions::update_points_vesting_handler(ctx, points_vesting_period)
+    }
+
+    // mint customer's vested pending reward points, callable by anyone
+    pub fn vest_points(ctx: Context<VestPoints>) -> Result<()> {
+        instructions::vest_points_handler(ctx)
+    }
+
+    // cancel customer's pending reward points for a refunded or charged back checkout
+    pub fn cancel_pending_points(ctx: Context<CancelPendingPoints>, points: u64) -> Result<()> {
+        instructions::cancel_pending_points_handler(ctx, points)
+    }
+
+    // set loyalty NFT level table, uri and reward points price of each level above the base NFT
+    pub fn update_loyalty_levels(
+        ctx: Context<UpdateLoyaltyLevels>,
+        levels: Vec<LoyaltyLevel>,
+    ) -> Result<()> {
+        instructions::update_loyalty_levels_handler(ctx, levels)
+    }
+
+    // burn reward points to move customer's loyalty NFT to the next level uri
+    pub fn upgrade_loyalty_nft(ctx: Context<UpgradeLoyaltyNft>) -> Result<()> {
+        instructions::upgrade_loyalty_nft_handler(ctx)
+    }
+
+    // mint a new versioned loyalty NFT to a customer who burned the previous version
+    pub fn reissue_loyalty_nft(ctx: Context<ReissueLoyaltyNft>, version: u32) -> Result<()> {
+        instructions::reissue_loyalty_nft_handler(ctx, version)
+    }
+
+    // soulbound loyalty NFTs are frozen in the member's token account after mint
+    pub fn update_soulbound_loyalty_nft(
+        ctx: Context<UpdateSoulboundLoyaltyNft>,
+        soulbound: bool,
+    ) -> Result<()> {
+        instructions::update_soulbound_loyalty_nft_handler(ctx, soulbound)
+    }
+
+    // thaw soulbound loyalty NFT, signed by merchant authority or NFT owner (e.g. wallet migration)
+    pub fn unlock_loyalty_nft(ctx: Context<UnlockLoyaltyNft>) -> Result<()> {
+        instructions::unlock_loyalty_nft_handler(ctx)
+    }
+
+    // revoke member's loyalty NFT, unverified from collection and checkout discount refused
+    pub fn revoke_membership(ctx: Context<RevokeMembership>) -> Result<()> {
+        instructions::revoke_membership_handler(ctx)
+    }
+
+    // update loyalty collection max members (0 = no cap) and member nft mint price in "usdc"
+    pub fn update_membership_config(
+        ctx: Context<UpdateMembershipConfig>,
+        max_members: u64,
+        member_mint_price: u64,
+    ) -> Result<()> {
+        instructions::update_membership_config_handler(ctx, max_members, member_mint_price)
+    }
+
+    // set member nft metadata template, member nfts are named "<name_prefix> #<member number>"
+    pub fn update_member_template(
+        ctx: Context<UpdateMemberTemplate>,
+        base_uri: String,
+        name_prefix: String,
+        symbol: String,
+    ) -> Result<()> {
+        instructions::update_member_template_handler(ctx, base_uri, name_prefix, symbol)
+    }
+
+    // update membership duration (seconds, 0 = never expires) and "usdc" renewal price
+    pub fn update_membership_term(
+        ctx: Context<UpdateMembershipTerm>,
+        membership_duration: i64,
+        renewal_price: u64,
+    ) -> Result<()> {
+        instructions::update_membership_term_handler(ctx, membership_duration, renewal_price)
+    }
+
+    // customer pays renewal price to extend membership by the merchant's membership duration
+    pub fn renew_membership(ctx: Context<RenewMembership>) -> Result<()> {
+        instructions::renew_membership_handler(ctx)
+    }
+
+    // update member nft uri to show expired state (or restore it after renewal), callable by anyone
+    pub fn refresh_membership_metadata(ctx: Context<RefreshMembershipMetadata>) -> Result<()> {
+        instructions::refresh_membership_metadata_handler(ctx)
+    }
+
+    // update member nft perk uses (e.g. 10 free coffees), 0 = no perks
+    pub fn update_perks(ctx: Context<UpdatePerks>, perk_uses: u64) -> Result<()> {
+        instructions::update_perks_handler(ctx, perk_uses)
+    }
+
+    // redeem member nft perks, merchant account is the approved use authority
+    pub fn redeem_perk(ctx: Context<RedeemPerk>, uses: u64) -> Result<()> {
+        instructions::redeem_perk_handler(ctx, uses)
+    }
+
+    // create member master nft for print edition membership
+    pub fn create_member_master(ctx: Context<CreateMemberMaster>) -> Result<()> {
+        instructions::create_member_master_handler(ctx)
+    }
+
+    // print edition member nfts are numbered print editions of the member master nft (edition = member number)
+    // print edition member nfts can't be upgraded, refreshed or reissued, metadata is immutable
+    pub fn update_print_edition_membership(
+        ctx: Context<UpdatePrintEditionMembership>,
+        print_edition_membership: bool,
+    ) -> Result<()> {
+        instructions::update_print_edition_membership_handler(ctx, print_edition_membership)
+    }
+
+    // programmable member nfts are minted as token metadata pNFTs with the merchant's rule set
+    // restricting transfers, they can't be soulbound, print editions or have perks
+    pub fn update_programmable_loyalty_nft(
+        ctx: Context<UpdateProgrammableLoyaltyNft>,
+        programmable_loyalty_nft: bool,
+        rule_set: Pubkey,
+    ) -> Result<()> {
+        instructions::update_programmable_loyalty_nft_handler(
+            ctx,
+            programmable_loyalty_nft,
+            rule_set,
+        )
+    }
+
+    // move collection nft out of the merchant's collection vault (e.g. program migration)
+    pub fn withdraw_collection_nft(ctx: Context<WithdrawCollectionNft>) -> Result<()> {
+        instructions::withdraw_collection_nft_handler(ctx)
+    }
+
+    // update collection nft metadata and creators, co-creators signing the transaction are verified
+    pub fn update_collection_metadata<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateCollectionMetadata<'info>>,
+        name: String,
+        symbol: String,
+        uri: String,
+        seller_fee_basis_points: u16,
+        creators: Vec<CollectionCreator>,
+    ) -> Result<()> {
+        instructions::update_collection_metadata_handler(
+            ctx,
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points,
+            creators,
+        )
+    }
+
+    // resize merchant account created by an earlier program version to the current layout
+    pub fn migrate_merchant(ctx: Context<MigrateMerchant>) -> Result<()> {
+        instructions::migrate_merchant_handler(ctx)
+    }
+
+    // create membership account for loyalty nft minted by an earlier program version
+    pub fn migrate_membership(ctx: Context<MigrateMembership>) -> Result<()> {
+        instructions::migrate_membership_handler(ctx)
+    }
 }
@@ -1,15 +1,516 @@
 use crate::*;
 
+// merchant lifecycle, reward points and loyalty collection are initialized independently:
+//
+//   Created (init_merchant)
+//     - valid: init_reward_points, create_collection_nft, merchant setting updates
+//       (update_* settings, fund_points_vault, init_coalition, member template and membership config)
+//   RewardPointsInitialized (reward_points_initialized, set by init_reward_points)
+//     - valid: transaction, mint_reward_points, redeem_voucher, redeem_points_for_cash, staking,
+//       vesting, swap and coalition points instructions, update_reward_points_metadata
+//     - init_reward_points fails with RewardPointsAlreadyInitialized
+//   LoyaltyCollectionInitialized (loyalty_collection_initialized, set by create_collection_nft)
+//     - valid: create_nft_in_collection, create_member_master, reissue/revoke/unlock/refresh member nfts,
+//       update_collection_metadata, withdraw_collection_nft
+//     - create_collection_nft fails with LoyaltyCollectionAlreadyInitialized
+//   upgrade_loyalty_nft requires both reward points and loyalty collection
+//
+// new fields are only appended, merchant accounts created by an earlier program version
+// are resized to MerchantState::LEN with migrate_merchant before use
 #[account]
 pub struct MerchantState {
-    pub authority: Pubkey,                  // 32
-    pub payment_destination: Pubkey,        // 32
-    pub reward_points_mint: Pubkey,         // 32
-    pub reward_points_basis_points: u16,    // 2
-    pub loyalty_collection_mint: Pubkey,    // 32
-    pub loyalty_discount_basis_points: u16, // 2
+    pub authority: Pubkey,                       // 32
+    pub payment_destination: Pubkey,             // 32
+    pub reward_points_mint: Pubkey,              // 32
+    pub reward_points_basis_points: u16,         // 2
+    pub loyalty_collection_mint: Pubkey,         // 32
+    pub loyalty_discount_basis_points: u16,      // 2
+    pub voucher_signer: Pubkey,                  // 32
+    pub max_points_supply: u64,                  // 8
+    pub streak_tiers: [StreakTier; 4],           // 4 * 4 (MAX_STREAK_TIERS)
+    pub cash_back_basis_points: u16,             // 2
+    pub min_redemption_points: u64,              // 8
+    pub coalition: Pubkey,                       // 32
+    pub non_transferable_points: bool,           // 1
+    pub stake_boost_basis_points: u16,           // 2
+    pub early_unstake_penalty_basis_points: u16, // 2
+    pub points_vesting_period: i64,              // 8
+    pub soulbound_loyalty_nft: bool,             // 1
+    pub max_members: u64,                        // 8
+    pub member_mint_price: u64,                  // 8
+    pub membership_duration: i64,                // 8
+    pub renewal_price: u64,                      // 8
+    pub perk_uses: u64,                          // 8
+    pub print_edition_membership: bool,          // 1
+    pub reward_points_initialized: bool,         // 1
+    pub loyalty_collection_initialized: bool,    // 1
+    pub min_streak_purchase: u64,                // 8
+    pub min_stake_amount: u64,                   // 8
+    pub pending_points_supply: u64,              // 8
+    pub programmable_loyalty_nft: bool,          // 1
+    pub loyalty_nft_rule_set: Pubkey,            // 32
 }
 
 impl MerchantState {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 2 + 32 + 2;
+    pub const LEN: usize = 8
+        + 32
+        + 32
+        + 32
+        + 2
+        + 32
+        + 2
+        + 32
+        + 8
+        + StreakTier::LEN * MAX_STREAK_TIERS
+        + 2
+        + 8
+        + 32
+        + 1
+        + 2
+        + 2
+        + 8
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 1
+        + 1
+        + 8
+        + 8
+        + 8
+        + 1
+        + 32;
+
+    // bonus multiplier (basis points, 10000 = 1x) for the highest streak tier reached
+    pub fn streak_multiplier_basis_points(&self, current_streak: u16) -> u16 {
+        self.streak_tiers
+            .iter()
+            .filter(|tier| tier.min_streak_days > 0 && current_streak >= tier.min_streak_days)
+            .map(|tier| tier.multiplier_basis_points)
+            .max()
+            .unwrap_or(10000)
+    }
+
+    // check minting `amount` reward points keeps supply within max_points_supply
+    // applies to the merchant's reward points mint, coalition points are not capped
+    // pending reward points reserved at checkout count towards supply until vested or cancelled
+    // emits a warning event for each supply threshold crossed by the mint
+    pub fn check_points_supply(&self, merchant: Pubkey, supply: u64, amount: u64) -> Result<()> {
+        let supply = supply
+            .checked_add(self.pending_points_supply)
+            .ok_or(GrizzlyError::MaxPointsSupplyExceeded)?;
+        let new_supply = supply
+            .checked_add(amount)
+            .ok_or(GrizzlyError::MaxPointsSupplyExceeded)?;
+        require!(
+            new_supply <= self.max_points_supply,
+            GrizzlyError::MaxPointsSupplyExceeded
+        );
+
+        for threshold_basis_points in POINTS_SUPPLY_WARNING_THRESHOLDS {
+            let threshold = (self.max_points_supply as u128)
+                .checked_mul(threshold_basis_points as u128)
+                .unwrap()
+                .checked_div(10000)
+                .unwrap() as u64;
+            if supply < threshold && new_supply >= threshold {
+                emit!(PointsSupplyWarning {
+                    merchant,
+                    supply: new_supply,
+                    max_points_supply: self.max_points_supply,
+                    threshold_basis_points,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // membership expiry for a membership starting (or renewed) at `start`, 0 = never expires
+    pub fn membership_expiry(&self, start: i64) -> Result<i64> {
+        if self.membership_duration > 0 {
+            Ok(start
+                .checked_add(self.membership_duration)
+                .ok_or(GrizzlyError::MathOverflow)?)
+        } else {
+            Ok(0)
+        }
+    }
+
+    // member nft perks (metadata uses), remaining uses exclude perks already redeemed by the member
+    pub fn member_perks(&self, perks_redeemed: u64) -> Option<Uses> {
+        if self.perk_uses > 0 {
+            Some(Uses {
+                use_method: UseMethod::Multiple,
+                remaining: self.perk_uses.saturating_sub(perks_redeemed),
+                total: self.perk_uses,
+            })
+        } else {
+            None
+        }
+    }
+
+    // check minting another member nft keeps the loyalty collection within max_members
+    pub fn check_member_cap(&self, collection_size: u64) -> Result<()> {
+        require!(
+            self.max_members == 0 || collection_size < self.max_members,
+            GrizzlyError::MaxMembersReached
+        );
+        Ok(())
+    }
+
+    // merchant's authorization rule set for programmable member nfts, default pubkey = no rule set
+    pub fn member_rule_set(&self) -> Option<Pubkey> {
+        if self.loyalty_nft_rule_set != Pubkey::default() {
+            Some(self.loyalty_nft_rule_set)
+        } else {
+            None
+        }
+    }
+
+    // programmable member nft token accounts are frozen by token metadata, so they can't be frozen
+    // as soulbound or approve the merchant as perk use authority, and can't be minted as print editions
+    pub fn check_programmable_loyalty_nft(&self) -> Result<()> {
+        require!(
+            !self.programmable_loyalty_nft
+                || (!self.soulbound_loyalty_nft
+                    && !self.print_edition_membership
+                    && self.perk_uses == 0),
+            GrizzlyError::ProgrammableLoyaltyNftIncompatible
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct VoucherNonce {
+    pub merchant: Pubkey, // 32
+    pub customer: Pubkey, // 32
+    pub nonce: u64,       // 8
+}
+
+impl VoucherNonce {
+    pub const LEN: usize = 8 + 32 + 32 + 8;
+}
+
+// purchase streak bonus, e.g. 5 days in a row = 20000 basis points (2x reward points)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct StreakTier {
+    pub min_streak_days: u16,         // 2
+    pub multiplier_basis_points: u16, // 2
+}
+
+impl StreakTier {
+    pub const LEN: usize = 2 + 2;
+}
+
+#[account]
+pub struct CustomerState {
+    pub merchant: Pubkey,                                      // 32
+    pub customer: Pubkey,                                      // 32
+    pub current_streak: u16,                                   // 2
+    pub longest_streak: u16,                                   // 2
+    pub last_qualifying_day: i64,                              // 8
+    pub staked_amount: u64,                                    // 8
+    pub stake_unlock_timestamp: i64,                           // 8
+    pub pending_points: u64,                                   // 8
+    pub pending_buckets: [PendingPoints; MAX_PENDING_BUCKETS], // 16 * 8 (MAX_PENDING_BUCKETS)
+    pub stake_boost_basis_points: u16,                         // 2
+    pub stake_penalty_basis_points: u16,                       // 2
+}
+
+impl CustomerState {
+    pub const LEN: usize =
+        8 + 32 + 32 + 2 + 2 + 8 + 8 + 8 + 8 + PendingPoints::LEN * MAX_PENDING_BUCKETS + 2 + 2;
+
+    // staked reward points boost checkout earnings until the stake unlocks
+    pub fn is_stake_locked(&self, now: i64) -> bool {
+        self.staked_amount > 0 && now < self.stake_unlock_timestamp
+    }
+
+    // update purchase streak for a purchase on `day` (days since unix epoch)
+    pub fn record_purchase_day(&mut self, day: i64) {
+        if self.current_streak == 0 || day > self.last_qualifying_day + 1 {
+            self.current_streak = 1;
+        } else if day == self.last_qualifying_day + 1 {
+            self.current_streak = self.current_streak.saturating_add(1);
+        }
+        self.longest_streak = self.longest_streak.max(self.current_streak);
+        self.last_qualifying_day = day;
+    }
+
+    // add checkout reward points vesting `vesting_period` seconds after `now`
+    // vest timestamps are rounded up so checkouts close together share a bucket, if all buckets
+    // are in use the points join the latest bucket, so pending points never vest early
+    pub fn add_pending_points(&mut self, amount: u64, now: i64, vesting_period: i64) -> Result<()> {
+        let granularity = vesting_period / (MAX_PENDING_BUCKETS as i64 - 1) + 1;
+        let vest_timestamp = now
+            .checked_add(vesting_period)
+            .and_then(|timestamp| timestamp.checked_add(granularity - 1))
+            .ok_or(GrizzlyError::MathOverflow)?
+            / granularity
+            * granularity;
+
+        let index = match self
+            .pending_buckets
+            .iter()
+            .position(|bucket| bucket.amount > 0 && bucket.vest_timestamp == vest_timestamp)
+            .or_else(|| {
+                self.pending_buckets
+                    .iter()
+                    .position(|bucket| bucket.amount == 0)
+            }) {
+            Some(index) => index,
+            None => {
+                let (index, _) = self
+                    .pending_buckets
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, bucket)| bucket.vest_timestamp)
+                    .unwrap();
+                index
+            }
+        };
+
+        let bucket = &mut self.pending_buckets[index];
+        bucket.amount = bucket
+            .amount
+            .checked_add(amount)
+            .ok_or(GrizzlyError::MathOverflow)?;
+        bucket.vest_timestamp = bucket.vest_timestamp.max(vest_timestamp);
+        self.pending_points = self
+            .pending_points
+            .checked_add(amount)
+            .ok_or(GrizzlyError::MathOverflow)?;
+        Ok(())
+    }
+
+    // remove and return pending points vested by `now`
+    pub fn take_vested_points(&mut self, now: i64) -> u64 {
+        let mut vested = 0u64;
+        for bucket in self.pending_buckets.iter_mut() {
+            if bucket.amount > 0 && bucket.vest_timestamp <= now {
+                vested = vested.checked_add(bucket.amount).unwrap();
+                *bucket = PendingPoints::default();
+            }
+        }
+        self.pending_points = self.pending_points.checked_sub(vested).unwrap();
+        vested
+    }
+
+    // remove `amount` pending points, latest vesting first (most recent checkouts are refunded)
+    pub fn cancel_pending_points(&mut self, amount: u64) -> Result<()> {
+        require!(
+            amount > 0 && amount <= self.pending_points,
+            GrizzlyError::InsufficientPendingPoints
+        );
+
+        let mut remaining = amount;
+        while remaining > 0 {
+            let bucket = self
+                .pending_buckets
+                .iter_mut()
+                .filter(|bucket| bucket.amount > 0)
+                .max_by_key(|bucket| bucket.vest_timestamp)
+                .unwrap();
+            let cancelled = remaining.min(bucket.amount);
+            bucket.amount -= cancelled;
+            if bucket.amount == 0 {
+                *bucket = PendingPoints::default();
+            }
+            remaining -= cancelled;
+        }
+        self.pending_points -= amount;
+        Ok(())
+    }
+}
+
+// reward points pending from checkouts, minted once vest_timestamp has passed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PendingPoints {
+    pub amount: u64,         // 8
+    pub vest_timestamp: i64, // 8
+}
+
+impl PendingPoints {
+    pub const LEN: usize = 8 + 8;
+}
+
+// coalition of merchants sharing one points mint (e.g. shopping mall)
+#[account]
+pub struct Coalition {
+    pub authority: Pubkey,   // 32
+    pub points_mint: Pubkey, // 32
+    pub member_count: u32,   // 4
+}
+
+impl Coalition {
+    pub const LEN: usize = 8 + 32 + 32 + 4;
+}
+
+// merchant's coalition ledger, coalition points issued vs redeemed for settlement between merchants
+#[account]
+pub struct CoalitionMember {
+    pub coalition: Pubkey,    // 32
+    pub merchant: Pubkey,     // 32
+    pub points_issued: u64,   // 8
+    pub points_redeemed: u64, // 8
+}
+
+impl CoalitionMember {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8;
+}
+
+// rate at which destination merchant accepts source merchant's reward points
+#[account]
+pub struct ExchangeRate {
+    pub destination_merchant: Pubkey, // 32
+    pub source_merchant: Pubkey,      // 32
+    pub rate_basis_points: u16,       // 2
+    pub daily_volume_cap: u64,        // 8
+    pub current_day: i64,             // 8
+    pub volume_today: u64,            // 8
+}
+
+impl ExchangeRate {
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 8 + 8 + 8;
+}
+
+// loyalty NFT level, level 1 is the first upgrade above the NFT as minted (level 0)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LoyaltyLevel {
+    pub uri: String,        // 4 + 200 (MAX_URI_LENGTH)
+    pub upgrade_price: u64, // 8
+}
+
+impl LoyaltyLevel {
+    pub const LEN: usize = 4 + MAX_URI_LENGTH + 8;
+}
+
+// collection nft creator, verified once the creator signs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CollectionCreator {
+    pub address: Pubkey, // 32
+    pub share: u8,       // 1
+}
+
+// merchant's loyalty NFT level table
+#[account]
+pub struct LoyaltyLevels {
+    pub merchant: Pubkey,          // 32
+    pub levels: Vec<LoyaltyLevel>, // 4 + LoyaltyLevel::LEN * MAX_LOYALTY_LEVELS
+}
+
+impl LoyaltyLevels {
+    pub const LEN: usize = 8 + 32 + 4 + LoyaltyLevel::LEN * MAX_LOYALTY_LEVELS;
+}
+
+// customer's loyalty NFT membership, created when the NFT is minted
+// loyalty NFTs minted by an earlier program version get one with migrate_membership
+#[account]
+pub struct Membership {
+    pub merchant: Pubkey,    // 32
+    pub nft_mint: Pubkey,    // 32
+    pub level: u8,           // 1
+    pub revoked: bool,       // 1
+    pub member_number: u64,  // 8
+    pub expiry: i64,         // 8
+    pub perks_redeemed: u64, // 8
+    pub print_edition: bool, // 1
+}
+
+impl Membership {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 1 + 8 + 8 + 8 + 1;
+
+    // memberships with an expiry of 0 never expire
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry > 0 && now >= self.expiry
+    }
+}
+
+// merchant's member nft metadata template, customers can not set member nft metadata
+#[account]
+pub struct MemberTemplate {
+    pub merchant: Pubkey,    // 32
+    pub base_uri: String,    // 4 + 175 (MAX_BASE_URI_LENGTH)
+    pub name_prefix: String, // 4 + 20 (MAX_NAME_PREFIX_LENGTH)
+    pub symbol: String,      // 4 + 10 (MAX_SYMBOL_LENGTH)
+    pub member_count: u64,   // 8
+}
+
+impl MemberTemplate {
+    // room for " #" and up to 10 digit member number
+    pub const MAX_NAME_PREFIX_LENGTH: usize = MAX_NAME_LENGTH - 12;
+    // room for up to 20 digit member number and ".json"
+    pub const MAX_BASE_URI_LENGTH: usize = MAX_URI_LENGTH - 25;
+    pub const LEN: usize = 8
+        + 32
+        + 4
+        + Self::MAX_BASE_URI_LENGTH
+        + 4
+        + Self::MAX_NAME_PREFIX_LENGTH
+        + 4
+        + MAX_SYMBOL_LENGTH
+        + 8;
+
+    // member nft uri, e.g. "<base_uri>42.json"
+    pub fn member_uri(&self, member_number: u64) -> String {
+        format!("{}{}.json", self.base_uri, member_number)
+    }
+
+    // uri shown on member nfts whose membership has expired
+    pub fn expired_uri(&self) -> String {
+        format!("{}expired.json", self.base_uri)
+    }
+
+    // member nft uri for the current membership state, the expired uri once expired,
+    // the loyalty level uri for upgraded members, otherwise the member number uri
+    pub fn membership_uri(
+        &self,
+        membership: &Membership,
+        loyalty_levels: Option<&LoyaltyLevels>,
+        now: i64,
+    ) -> Result<String> {
+        if membership.is_expired(now) {
+            Ok(self.expired_uri())
+        } else if membership.level > 0 {
+            Ok(loyalty_levels
+                .ok_or(GrizzlyError::InvalidLoyaltyLevels)?
+                .levels
+                .get(membership.level as usize - 1)
+                .ok_or(GrizzlyError::InvalidLoyaltyLevels)?
+                .uri
+                .clone())
+        } else {
+            Ok(self.member_uri(membership.member_number))
+        }
+    }
+
+    // member master nft metadata shared by all print editions, uri "<base_uri>edition.json"
+    pub fn edition_data(&self) -> DataV2 {
+        DataV2 {
+            name: self.name_prefix.clone(),
+            symbol: self.symbol.clone(),
+            uri: format!("{}edition.json", self.base_uri),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+
+    // member nft metadata, e.g. "Grizzly Member #42" with uri "<base_uri>42.json"
+    pub fn member_data(&self, member_number: u64) -> DataV2 {
+        DataV2 {
+            name: format!("{} #{}", self.name_prefix, member_number),
+            symbol: self.symbol.clone(),
+            uri: self.member_uri(member_number),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
 }
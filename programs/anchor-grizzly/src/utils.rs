@@ -0,0 +1,885 @@
+use crate::*;
+
+// convert a token amount between mints with different decimals, applying a basis points rate
+// e.g. 1% of a 6 decimal "usdc" amount expressed in 0 decimal reward points
+pub fn convert_amount(
+    amount: u64,
+    basis_points: u16,
+    from_decimals: u8,
+    to_decimals: u8,
+) -> Result<u64> {
+    let to_scale = 10u128
+        .checked_pow(to_decimals as u32)
+        .ok_or(GrizzlyError::MathOverflow)?;
+    let from_scale = 10u128
+        .checked_pow(from_decimals as u32)
+        .ok_or(GrizzlyError::MathOverflow)?;
+    let converted = (amount as u128)
+        .checked_mul(basis_points as u128)
+        .ok_or(GrizzlyError::MathOverflow)?
+        .checked_mul(to_scale)
+        .ok_or(GrizzlyError::MathOverflow)?
+        .checked_div(10000)
+        .unwrap()
+        .checked_div(from_scale)
+        .unwrap();
+    Ok(u64::try_from(converted).map_err(|_| GrizzlyError::MathOverflow)?)
+}
+
+// non-transferable reward points: customer token accounts stay frozen by the reward points mint PDA
+// (freeze authority) and are only thawed within instructions that mint or burn reward points
+pub fn thaw_reward_points_account<'info>(
+    token_program: &Program<'info, Token>,
+    reward_points_mint: &Account<'info, Mint>,
+    token_account: &Account<'info, TokenAccount>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if !token_account.is_frozen() {
+        return Ok(());
+    }
+    thaw_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        ThawAccount {
+            account: token_account.to_account_info(),
+            mint: reward_points_mint.to_account_info(),
+            authority: reward_points_mint.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+// freeze customer token account again after minting or burning non-transferable reward points
+pub fn freeze_reward_points_account<'info>(
+    token_program: &Program<'info, Token>,
+    reward_points_mint: &Account<'info, Mint>,
+    token_account: &Account<'info, TokenAccount>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    freeze_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        FreezeAccount {
+            account: token_account.to_account_info(),
+            mint: reward_points_mint.to_account_info(),
+            authority: reward_points_mint.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+// create a PDA account owned by `owner`, like anchor's init but run from the handler so the
+// merchant's init flag constraint is checked first, handles a PDA already funded with lamports
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            rent_exempt,
+            space as u64,
+            owner,
+        );
+    }
+
+    let top_up = rent_exempt.saturating_sub(account.lamports());
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
+
+// create a mint at its PDA, the PDA is its own mint and freeze authority
+pub fn create_pda_mint<'info>(
+    payer: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    create_pda_account(
+        payer,
+        mint,
+        system_program,
+        Mint::LEN,
+        token_program.key,
+        signer_seeds,
+    )?;
+    initialize_mint2(
+        CpiContext::new(
+            token_program.clone(),
+            InitializeMint2 { mint: mint.clone() },
+        ),
+        decimals,
+        mint.key,
+        Some(mint.key),
+    )
+}
+
+// read loyalty NFT metadata as DataV2, metadata strings are stored padded with null bytes
+// collection and uses must be passed back unchanged when updating a verified collection item
+pub fn loyalty_nft_data(metadata_account: &AccountInfo) -> Result<DataV2> {
+    let metadata = MetadataState::from_account_info(metadata_account)?;
+    Ok(DataV2 {
+        name: metadata.data.name.trim_end_matches('\0').to_string(),
+        symbol: metadata.data.symbol.trim_end_matches('\0').to_string(),
+        uri: metadata.data.uri.trim_end_matches('\0').to_string(),
+        seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+        creators: metadata.data.creators,
+        collection: metadata.collection,
+        uses: metadata.uses,
+    })
+}
+
+// print edition member nfts are created immutable by token metadata, so their uri can't follow
+// loyalty level or membership expiry, rejected up front instead of failing in the metadata update
+pub fn require_mutable_loyalty_nft(metadata_account: &AccountInfo) -> Result<()> {
+    let metadata = MetadataState::from_account_info(metadata_account)?;
+    require!(
+        metadata.is_mutable,
+        GrizzlyError::PrintEditionMetadataImmutable
+    );
+    Ok(())
+}
+
+// number of verified loyalty NFTs in the merchant's sized collection
+pub fn loyalty_collection_size(collection_metadata_account: &AccountInfo) -> Result<u64> {
+    let collection_metadata = MetadataState::from_account_info(collection_metadata_account)?;
+    match collection_metadata.collection_details {
+        Some(CollectionDetails::V1 { size }) => Ok(size),
+        None => Ok(0),
+    }
+}
+
+// accounts for minting a verified loyalty NFT in the merchant's collection
+// member nft royalty and creators inherited from the collection nft
+// merchant account is added as a 0 share creator, verified with sign_metadata when minted
+pub fn member_royalties(
+    collection_metadata_account: &AccountInfo,
+    merchant: Pubkey,
+) -> Result<(u16, Option<Vec<Creator>>)> {
+    let collection = loyalty_nft_data(collection_metadata_account)?;
+    let mut creators = vec![Creator {
+        address: merchant,
+        verified: false,
+        share: 0,
+    }];
+    creators.extend(
+        collection
+            .creators
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|creator| creator.address != merchant)
+            .map(|creator| Creator {
+                verified: false,
+                ..creator
+            }),
+    );
+    Ok((collection.seller_fee_basis_points, Some(creators)))
+}
+
+// validate collection royalty and creator split, leaving room for the merchant account as member nft creator
+// the merchant account is added to member nfts with a 0 share, so it can't hold a collection share
+pub fn validate_royalties(
+    seller_fee_basis_points: u16,
+    creators: &[CollectionCreator],
+    merchant: Pubkey,
+) -> Result<()> {
+    require!(
+        seller_fee_basis_points <= 10000,
+        GrizzlyError::InvalidBasisPoints
+    );
+    require!(
+        !creators.is_empty()
+            && creators.len() < MAX_CREATOR_LIMIT
+            && creators
+                .iter()
+                .map(|creator| creator.share as u16)
+                .sum::<u16>()
+                == 100,
+        GrizzlyError::InvalidCreatorShares
+    );
+    require!(
+        creators.iter().all(|creator| creator.address != merchant),
+        GrizzlyError::MerchantCollectionCreator
+    );
+    Ok(())
+}
+
+pub struct MintLoyaltyNft<'info> {
+    pub payer: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
+    pub loyalty_collection_mint: AccountInfo<'info>,
+    pub collection_metadata_account: AccountInfo<'info>,
+    pub collection_master_edition: AccountInfo<'info>,
+    pub nft_mint: AccountInfo<'info>,
+    pub metadata_account: AccountInfo<'info>,
+    pub master_edition: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    // merchant account, verified creator and perk use authority of member nfts
+    pub merchant: AccountInfo<'info>,
+    pub use_authority_record: AccountInfo<'info>,
+    pub burner: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+    pub print_edition: Option<PrintEdition<'info>>,
+    pub programmable: Option<ProgrammableNft<'info>>,
+}
+
+// merchant's member master nft accounts for minting a member nft as a print edition
+pub struct PrintEdition<'info> {
+    pub master_mint: AccountInfo<'info>,
+    pub master_metadata: AccountInfo<'info>,
+    pub master_edition: AccountInfo<'info>,
+    pub master_token_account: AccountInfo<'info>,
+    pub edition_marker: AccountInfo<'info>,
+    pub edition: u64,
+}
+
+// token metadata accounts for minting a member nft as a programmable nft
+pub struct ProgrammableNft<'info> {
+    pub token_record: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub authorization_rules: Option<AuthorizationRules<'info>>,
+}
+
+// rule set restricting programmable member nft transfers, and the token auth rules program
+pub struct AuthorizationRules<'info> {
+    pub program: AccountInfo<'info>,
+    pub rule_set: AccountInfo<'info>,
+}
+
+// token metadata verify and unverify instructions, mpl-token-metadata 1.8.3 predates their
+// deployed args (CreatorV1 = 0, CollectionV1 = 1) so they are built by hand
+const VERIFY_INSTRUCTION: u8 = 52;
+const UNVERIFY_INSTRUCTION: u8 = 53;
+const CREATOR_V1: u8 = 0;
+const COLLECTION_V1: u8 = 1;
+
+// programmable member nft accounts, required when the merchant mints programmable member nfts
+// rule set accounts are required if the merchant set a rule set
+pub fn programmable_nft<'info>(
+    merchant: &MerchantState,
+    token_record: Option<&UncheckedAccount<'info>>,
+    sysvar_instructions: Option<&UncheckedAccount<'info>>,
+    associated_token_program: &AccountInfo<'info>,
+    authorization_rules_program: Option<&UncheckedAccount<'info>>,
+    authorization_rules: Option<&UncheckedAccount<'info>>,
+) -> Result<Option<ProgrammableNft<'info>>> {
+    if !merchant.programmable_loyalty_nft {
+        return Ok(None);
+    }
+    let (token_record, sysvar_instructions) = match (token_record, sysvar_instructions) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return err!(GrizzlyError::ProgrammableLoyaltyNftAccountsRequired),
+    };
+    let authorization_rules = match merchant.member_rule_set() {
+        Some(_) => match (authorization_rules_program, authorization_rules) {
+            (Some(program), Some(rule_set)) => Some(AuthorizationRules {
+                program: program.to_account_info(),
+                rule_set: rule_set.to_account_info(),
+            }),
+            _ => return err!(GrizzlyError::ProgrammableLoyaltyNftAccountsRequired),
+        },
+        None => None,
+    };
+    Ok(Some(ProgrammableNft {
+        token_record: token_record.to_account_info(),
+        sysvar_instructions: sysvar_instructions.to_account_info(),
+        associated_token_program: associated_token_program.clone(),
+        authorization_rules,
+    }))
+}
+
+// programmable member nfts are minted with token metadata's create instruction, metadata
+// token standard tells them apart from other member nfts
+pub fn is_programmable_loyalty_nft(metadata_account: &AccountInfo) -> Result<bool> {
+    let metadata = MetadataState::from_account_info(metadata_account)?;
+    Ok(metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible))
+}
+
+// mint 1 loyalty nft, create metadata and master edition, and verify nft as part of collection
+// loyalty collection mint PDA is mint authority, update authority and collection authority
+// and is approved as delegate of the owner's token account so it can freeze the nft (soulbound)
+// member nfts with perks (metadata uses) approve the merchant PDA as use authority
+// programmable member nfts are minted with token metadata's create and mint instructions instead
+pub fn mint_loyalty_nft(
+    mut accounts: MintLoyaltyNft,
+    data: DataV2,
+    soulbound: bool,
+    signer_seeds: &[&[&[u8]]],
+    merchant_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if let Some(programmable) = accounts.programmable.take() {
+        return mint_programmable_loyalty_nft(
+            accounts,
+            programmable,
+            data,
+            signer_seeds,
+            merchant_signer_seeds,
+        );
+    }
+
+    // mint 1 nft to customer token account
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_program.clone(),
+        MintTo {
+            mint: accounts.nft_mint.clone(),
+            to: accounts.token_account.clone(),
+            authority: accounts.loyalty_collection_mint.clone(),
+        },
+        signer_seeds,
+    );
+    mint_to(cpi_ctx, 1)?;
+
+    match accounts.print_edition {
+        // mint numbered print edition from merchant's member master nft
+        // token metadata copies the master's data and creates the metadata immutable
+        Some(print_edition) => {
+            mint_new_edition_from_master_edition_via_token(
+                CpiContext::new_with_signer(
+                    accounts.token_metadata_program.clone(),
+                    MintNewEditionFromMasterEditionViaToken {
+                        new_metadata: accounts.metadata_account.clone(),
+                        new_edition: accounts.master_edition.clone(),
+                        master_edition: print_edition.master_edition,
+                        new_mint: accounts.nft_mint.clone(),
+                        edition_mark_pda: print_edition.edition_marker,
+                        new_mint_authority: accounts.loyalty_collection_mint.clone(),
+                        payer: accounts.payer.clone(),
+                        token_account_owner: accounts.loyalty_collection_mint.clone(),
+                        token_account: print_edition.master_token_account,
+                        new_metadata_update_authority: accounts.loyalty_collection_mint.clone(),
+                        metadata: print_edition.master_metadata,
+                        token_program: accounts.token_program.clone(),
+                        system_program: accounts.system_program.clone(),
+                        rent: accounts.rent.clone(),
+                        metadata_mint: print_edition.master_mint,
+                    },
+                    signer_seeds,
+                ),
+                print_edition.edition,
+            )?;
+        }
+        None => {
+            // create metadata account
+            create_metadata_accounts_v3(
+                CpiContext::new_with_signer(
+                    accounts.token_metadata_program.clone(),
+                    CreateMetadataAccountsV3 {
+                        metadata: accounts.metadata_account.clone(),
+                        mint: accounts.nft_mint.clone(),
+                        mint_authority: accounts.loyalty_collection_mint.clone(),
+                        update_authority: accounts.loyalty_collection_mint.clone(),
+                        payer: accounts.payer.clone(),
+                        system_program: accounts.system_program.clone(),
+                        rent: accounts.rent.clone(),
+                    },
+                    signer_seeds,
+                ),
+                data,
+                true,
+                true,
+                None,
+            )?;
+
+            // verify merchant account as creator, print editions copy the master's verified creators
+            sign_metadata(CpiContext::new_with_signer(
+                accounts.token_metadata_program.clone(),
+                SignMetadata {
+                    creator: accounts.merchant.clone(),
+                    metadata: accounts.metadata_account.clone(),
+                },
+                merchant_signer_seeds,
+            ))?;
+
+            // create master edition account
+            create_master_edition_v3(
+                CpiContext::new_with_signer(
+                    accounts.token_metadata_program.clone(),
+                    CreateMasterEditionV3 {
+                        payer: accounts.payer.clone(),
+                        mint: accounts.nft_mint.clone(),
+                        edition: accounts.master_edition.clone(),
+                        mint_authority: accounts.loyalty_collection_mint.clone(),
+                        update_authority: accounts.loyalty_collection_mint.clone(),
+                        metadata: accounts.metadata_account.clone(),
+                        token_program: accounts.token_program.clone(),
+                        system_program: accounts.system_program.clone(),
+                        rent: accounts.rent.clone(),
+                    },
+                    signer_seeds,
+                ),
+                Some(0),
+            )?;
+        }
+    }
+
+    // print editions copy their uses from the master, so read them back from the new metadata
+    let data_uses = MetadataState::from_account_info(&accounts.metadata_account)?.uses;
+
+    // verify nft as part of collection
+    set_and_verify_sized_collection_item(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.clone(),
+            SetAndVerifySizedCollectionItem {
+                metadata: accounts.metadata_account.clone(),
+                collection_authority: accounts.loyalty_collection_mint.clone(),
+                payer: accounts.payer.clone(),
+                update_authority: accounts.loyalty_collection_mint.clone(),
+                collection_mint: accounts.loyalty_collection_mint.clone(),
+                collection_metadata: accounts.collection_metadata_account,
+                collection_master_edition: accounts.collection_master_edition,
+            },
+            signer_seeds,
+        ),
+        None,
+    )?;
+
+    // approve loyalty collection mint PDA as delegate of owner's nft token account
+    approve(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            Approve {
+                to: accounts.token_account.clone(),
+                delegate: accounts.loyalty_collection_mint.clone(),
+                authority: accounts.owner.clone(),
+            },
+        ),
+        1,
+    )?;
+
+    // approve merchant PDA as use authority for the nft's remaining perk uses
+    // anchor-spl has no wrapper for approve_use_authority
+    if let Some(uses) = data_uses.filter(|uses| uses.remaining > 0) {
+        let ix = approve_use_authority(
+            accounts.token_metadata_program.key(),
+            accounts.use_authority_record.key(),
+            accounts.merchant.key(),
+            accounts.owner.key(),
+            accounts.payer.key(),
+            accounts.token_account.key(),
+            accounts.metadata_account.key(),
+            accounts.nft_mint.key(),
+            accounts.burner.key(),
+            uses.remaining,
+        );
+        invoke(
+            &ix,
+            &[
+                accounts.use_authority_record,
+                accounts.owner,
+                accounts.payer,
+                accounts.merchant.clone(),
+                accounts.token_account.clone(),
+                accounts.metadata_account.clone(),
+                accounts.nft_mint.clone(),
+                accounts.burner,
+                accounts.token_program.clone(),
+                accounts.system_program,
+                accounts.token_metadata_program.clone(),
+            ],
+        )?;
+    }
+
+    // freeze soulbound nft, mint freeze authority is now the master edition
+    if soulbound {
+        freeze_delegated_account(CpiContext::new_with_signer(
+            accounts.token_metadata_program,
+            FreezeDelegatedAccount {
+                metadata: accounts.metadata_account,
+                delegate: accounts.loyalty_collection_mint,
+                token_account: accounts.token_account,
+                edition: accounts.master_edition,
+                mint: accounts.nft_mint,
+                token_program: accounts.token_program,
+            },
+            signer_seeds,
+        ))?;
+    }
+    Ok(())
+}
+
+// unverify loyalty nft from collection, decrements collection size
+// anchor-spl has no wrapper for unverify_sized_collection_item
+pub fn unverify_loyalty_nft<'info>(
+    token_metadata_program: &AccountInfo<'info>,
+    metadata_account: &AccountInfo<'info>,
+    loyalty_collection_mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    collection_metadata_account: &AccountInfo<'info>,
+    collection_master_edition: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = unverify_sized_collection_item(
+        token_metadata_program.key(),
+        metadata_account.key(),
+        loyalty_collection_mint.key(),
+        payer.key(),
+        loyalty_collection_mint.key(),
+        collection_metadata_account.key(),
+        collection_master_edition.key(),
+        None,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            metadata_account.clone(),
+            loyalty_collection_mint.clone(),
+            payer.clone(),
+            collection_metadata_account.clone(),
+            collection_master_edition.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// mint 1 programmable loyalty nft with token metadata create and mint, then verify the collection
+// and merchant creator, token metadata freezes the owner's token account and creates its token record
+// programmable member nfts are never soulbound, print editions or have perks
+fn mint_programmable_loyalty_nft<'info>(
+    accounts: MintLoyaltyNft<'info>,
+    programmable: ProgrammableNft<'info>,
+    data: DataV2,
+    signer_seeds: &[&[&[u8]]],
+    merchant_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let token_metadata_program = accounts.token_metadata_program.key();
+
+    // create metadata and master edition, collection is verified after mint
+    let mut asset_data = AssetData::new(
+        TokenStandard::ProgrammableNonFungible,
+        data.name,
+        data.symbol,
+        data.uri,
+    );
+    asset_data.seller_fee_basis_points = data.seller_fee_basis_points;
+    asset_data.creators = data.creators;
+    asset_data.collection = Some(Collection {
+        verified: false,
+        key: accounts.loyalty_collection_mint.key(),
+    });
+    asset_data.rule_set = programmable
+        .authorization_rules
+        .as_ref()
+        .map(|rules| rules.rule_set.key());
+
+    let ix = CreateBuilder::new()
+        .metadata(accounts.metadata_account.key())
+        .master_edition(accounts.master_edition.key())
+        .mint(accounts.nft_mint.key())
+        .authority(accounts.loyalty_collection_mint.key())
+        .payer(accounts.payer.key())
+        .update_authority(accounts.loyalty_collection_mint.key())
+        .initialize_mint(false)
+        .update_authority_as_signer(true)
+        .build(CreateArgs::V1 {
+            asset_data,
+            decimals: Some(0),
+            print_supply: Some(PrintSupply::Zero),
+        })
+        .map_err(|_| ProgramError::InvalidArgument)?
+        .instruction();
+    invoke_signed(
+        &ix,
+        &[
+            accounts.metadata_account.clone(),
+            accounts.master_edition.clone(),
+            accounts.nft_mint.clone(),
+            accounts.loyalty_collection_mint.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            programmable.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            accounts.token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    // mint 1 nft to owner token account, signed by the loyalty collection mint PDA as update authority
+    let mut builder = MintBuilder::new();
+    builder
+        .token(accounts.token_account.key())
+        .token_owner(accounts.owner.key())
+        .metadata(accounts.metadata_account.key())
+        .master_edition(accounts.master_edition.key())
+        .token_record(programmable.token_record.key())
+        .mint(accounts.nft_mint.key())
+        .authority(accounts.loyalty_collection_mint.key())
+        .payer(accounts.payer.key());
+    let mut mint_accounts = vec![
+        accounts.token_account,
+        accounts.owner,
+        accounts.metadata_account.clone(),
+        accounts.master_edition,
+        programmable.token_record,
+        accounts.nft_mint,
+        accounts.loyalty_collection_mint.clone(),
+        accounts.payer,
+        accounts.system_program.clone(),
+        programmable.sysvar_instructions.clone(),
+        accounts.token_program,
+        programmable.associated_token_program,
+        accounts.token_metadata_program.clone(),
+    ];
+    if let Some(rules) = programmable.authorization_rules {
+        builder
+            .authorization_rules_program(rules.program.key())
+            .authorization_rules(rules.rule_set.key());
+        mint_accounts.push(rules.program);
+        mint_accounts.push(rules.rule_set);
+    }
+    let ix = builder
+        .build(MintArgs::V1 {
+            amount: 1,
+            authorization_data: None,
+        })
+        .map_err(|_| ProgramError::InvalidArgument)?
+        .instruction();
+    invoke_signed(&ix, &mint_accounts, signer_seeds)?;
+
+    // verify nft as part of collection, increments collection size
+    let ix = Instruction {
+        program_id: token_metadata_program,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.loyalty_collection_mint.key(), true),
+            AccountMeta::new_readonly(token_metadata_program, false),
+            AccountMeta::new(accounts.metadata_account.key(), false),
+            AccountMeta::new_readonly(accounts.loyalty_collection_mint.key(), false),
+            AccountMeta::new(accounts.collection_metadata_account.key(), false),
+            AccountMeta::new_readonly(accounts.collection_master_edition.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(programmable.sysvar_instructions.key(), false),
+        ],
+        data: vec![VERIFY_INSTRUCTION, COLLECTION_V1],
+    };
+    invoke_signed(
+        &ix,
+        &[
+            accounts.loyalty_collection_mint,
+            accounts.metadata_account.clone(),
+            accounts.collection_metadata_account,
+            accounts.collection_master_edition,
+            accounts.system_program.clone(),
+            programmable.sysvar_instructions.clone(),
+            accounts.token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    // verify merchant account as creator
+    let ix = Instruction {
+        program_id: token_metadata_program,
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.merchant.key(), true),
+            AccountMeta::new_readonly(token_metadata_program, false),
+            AccountMeta::new(accounts.metadata_account.key(), false),
+            AccountMeta::new_readonly(token_metadata_program, false),
+            AccountMeta::new_readonly(token_metadata_program, false),
+            AccountMeta::new_readonly(token_metadata_program, false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(programmable.sysvar_instructions.key(), false),
+        ],
+        data: vec![VERIFY_INSTRUCTION, CREATOR_V1],
+    };
+    invoke_signed(
+        &ix,
+        &[
+            accounts.merchant,
+            accounts.metadata_account,
+            accounts.system_program,
+            programmable.sysvar_instructions,
+            accounts.token_metadata_program,
+        ],
+        merchant_signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// unverify programmable loyalty nft from collection with token metadata's unverify instruction,
+// decrements collection size, the legacy unverify instruction rejects programmable nfts
+pub fn unverify_programmable_loyalty_nft<'info>(
+    token_metadata_program: &AccountInfo<'info>,
+    metadata_account: &AccountInfo<'info>,
+    loyalty_collection_mint: &AccountInfo<'info>,
+    collection_metadata_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    sysvar_instructions: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: token_metadata_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(loyalty_collection_mint.key(), true),
+            AccountMeta::new_readonly(token_metadata_program.key(), false),
+            AccountMeta::new(metadata_account.key(), false),
+            AccountMeta::new_readonly(loyalty_collection_mint.key(), false),
+            AccountMeta::new(collection_metadata_account.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(sysvar_instructions.key(), false),
+        ],
+        data: vec![UNVERIFY_INSTRUCTION, COLLECTION_V1],
+    };
+    invoke_signed(
+        &ix,
+        &[
+            loyalty_collection_mint.clone(),
+            metadata_account.clone(),
+            collection_metadata_account.clone(),
+            system_program.clone(),
+            sysvar_instructions.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+pub struct UpdateLoyaltyNft<'info> {
+    pub loyalty_collection_mint: AccountInfo<'info>,
+    pub nft_mint: AccountInfo<'info>,
+    pub metadata_account: AccountInfo<'info>,
+    pub token_metadata_program: AccountInfo<'info>,
+    // required for programmable member nfts, rule set accounts if the nft has a rule set
+    pub system_program: Option<AccountInfo<'info>>,
+    pub sysvar_instructions: Option<AccountInfo<'info>>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+// update loyalty nft metadata data, signed by the loyalty collection mint PDA as update authority
+// programmable member nfts are updated with token metadata's update instruction, which requires
+// the rule set the nft was minted with, other member nfts with update_metadata_accounts_v2
+pub fn update_loyalty_nft_data(
+    accounts: UpdateLoyaltyNft,
+    data: DataV2,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metadata = MetadataState::from_account_info(&accounts.metadata_account)?;
+    if metadata.token_standard != Some(TokenStandard::ProgrammableNonFungible) {
+        return update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                accounts.token_metadata_program,
+                UpdateMetadataAccountsV2 {
+                    metadata: accounts.metadata_account,
+                    update_authority: accounts.loyalty_collection_mint,
+                },
+                signer_seeds,
+            ),
+            None,
+            Some(data),
+            None,
+            None,
+        );
+    }
+
+    let (system_program, sysvar_instructions) =
+        match (accounts.system_program, accounts.sysvar_instructions) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return err!(GrizzlyError::ProgrammableLoyaltyNftAccountsRequired),
+        };
+
+    // loyalty collection mint PDA also pays, metadata data is fixed size so nothing is charged
+    let mut builder = UpdateBuilder::new();
+    builder
+        .authority(accounts.loyalty_collection_mint.key())
+        .mint(accounts.nft_mint.key())
+        .metadata(accounts.metadata_account.key())
+        .payer(accounts.loyalty_collection_mint.key());
+    let mut update_accounts = vec![
+        accounts.loyalty_collection_mint,
+        accounts.nft_mint,
+        accounts.metadata_account,
+        system_program,
+        sysvar_instructions,
+        accounts.token_metadata_program,
+    ];
+    if let Some(ProgrammableConfig::V1 {
+        rule_set: Some(rule_set),
+    }) = metadata.programmable_config
+    {
+        let (program, rules) = match (
+            accounts.authorization_rules_program,
+            accounts.authorization_rules,
+        ) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return err!(GrizzlyError::ProgrammableLoyaltyNftAccountsRequired),
+        };
+        require_keys_eq!(
+            rules.key(),
+            rule_set,
+            GrizzlyError::ProgrammableLoyaltyNftAccountsRequired
+        );
+        builder
+            .authorization_rules_program(program.key())
+            .authorization_rules(rules.key());
+        update_accounts.push(program);
+        update_accounts.push(rules);
+    }
+    let ix = builder
+        .build(UpdateArgs::V1 {
+            new_update_authority: None,
+            data: Some(Data {
+                name: data.name,
+                symbol: data.symbol,
+                uri: data.uri,
+                seller_fee_basis_points: data.seller_fee_basis_points,
+                creators: data.creators,
+            }),
+            primary_sale_happened: None,
+            is_mutable: None,
+            collection: CollectionToggle::None,
+            collection_details: CollectionDetailsToggle::None,
+            uses: UsesToggle::None,
+            rule_set: RuleSetToggle::None,
+            authorization_data: None,
+        })
+        .map_err(|_| ProgramError::InvalidArgument)?
+        .instruction();
+    invoke_signed(&ix, &update_accounts, signer_seeds).map_err(Into::into)
+}
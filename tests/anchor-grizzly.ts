@@ -7,6 +7,7 @@ import { Metaplex } from "@metaplex-foundation/js"
 import {
   Metadata,
   PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+  TokenStandard,
 } from "@metaplex-foundation/mpl-token-metadata"
 import NodeWallet from "@project-serum/anchor/dist/cjs/nodewallet"
 const fs = require("fs")
@@ -21,6 +22,9 @@ describe("anchor-grizzly", () => {
 
   const customer = anchor.web3.Keypair.generate()
 
+  // off-chain key signing reward points vouchers (e.g. POS backend)
+  const voucherSigner = anchor.web3.Keypair.generate()
+
   // merchant account
   const [merchantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
     [Buffer.from("MERCHANT"), wallet.publicKey.toBuffer()],
@@ -49,6 +53,32 @@ describe("anchor-grizzly", () => {
     program.programId
   )
 
+  // customer account for merchant, tracks purchase streak
+  const [customerStatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("CUSTOMER"),
+      merchantPDA.toBuffer(),
+      customer.publicKey.toBuffer(),
+    ],
+    program.programId
+  )
+
+  // customer stake vault for merchant reward points
+  const [stakeVaultPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("STAKE_VAULT"),
+      merchantPDA.toBuffer(),
+      customer.publicKey.toBuffer(),
+    ],
+    program.programId
+  )
+
+  // merchant "usdc" points vault, backs reward points cash back
+  const [pointsVaultPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("POINTS_VAULT"), merchantPDA.toBuffer()],
+    program.programId
+  )
+
   // test nft metadata
   const testMetadata = {
     uri: "https://arweave.net/h19GMcMz7RLDY7kAHGWeWolHTmO83mLLMNPzEkF32BQ",
@@ -56,6 +86,81 @@ describe("anchor-grizzly", () => {
     symbol: "SYMBOL",
   }
 
+  // token metadata burner PDA, required when approving member nft use authority
+  const [burnerPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      Buffer.from("burn"),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  )
+
+  // use authority record approving merchant account to redeem member nft perks
+  function useAuthorityRecordPDA(nftMint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        nftMint.toBuffer(),
+        Buffer.from("user"),
+        merchantPDA.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0]
+  }
+
+  // token record of a programmable member nft token account
+  function tokenRecordPDA(
+    nftMint: anchor.web3.PublicKey,
+    tokenAccount: anchor.web3.PublicKey
+  ) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        nftMint.toBuffer(),
+        Buffer.from("token_record"),
+        tokenAccount.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0]
+  }
+
+  // collection nft vault, owned by merchant account
+  const [collectionVaultPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("COLLECTION_VAULT"), merchantPDA.toBuffer()],
+    program.programId
+  )
+
+  // merchant member master nft, held by loyalty collection mint PDA
+  const [memberMasterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("MEMBER_MASTER"), merchantPDA.toBuffer()],
+    program.programId
+  )
+
+  // edition marker for member master print edition, one marker per 248 editions
+  function editionMarkerPDA(edition: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        memberMasterPDA.toBuffer(),
+        Buffer.from("edition"),
+        Buffer.from(Math.floor(edition / 248).toString()),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0]
+  }
+
+  // merchant member nft template
+  const memberTemplate = {
+    baseUri: "https://arweave.net/grizzly-members/",
+  }
+
+  // reward points mint decimals, "usdc" placeholder mint uses 0 decimals
+  const rewardPointsDecimals = 6
+
   // customer account
 
   let usdcPlaceholderMint: anchor.web3.PublicKey
@@ -129,6 +234,205 @@ describe("anchor-grizzly", () => {
     )
   })
 
+  // init an additional merchant and reward points mint, returns payment destination
+  async function initTestMerchant(
+    authority: anchor.web3.Keypair,
+    nonTransferable = false
+  ) {
+    const txSig = await connection.requestAirdrop(
+      authority.publicKey,
+      1 * anchor.web3.LAMPORTS_PER_SOL
+    )
+
+    const { blockhash, lastValidBlockHeight } =
+      await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction(
+      {
+        blockhash,
+        lastValidBlockHeight,
+        signature: txSig,
+      },
+      "confirmed"
+    )
+
+    const [merchant] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MERCHANT"), authority.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [rewardPoints] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("REWARD_POINTS"), merchant.toBuffer()],
+      program.programId
+    )
+
+    const merchantPaymentDestination = await spl.getAssociatedTokenAddress(
+      usdcPlaceholderMint,
+      authority.publicKey
+    )
+
+    await program.methods
+      .initMerchant()
+      .accounts({
+        authority: authority.publicKey,
+        usdcMintPlaceholder: usdcPlaceholderMint,
+        paymentDestination: merchantPaymentDestination,
+      })
+      .signers([authority])
+      .rpc()
+
+    const rewardPointsMetadataPDA = await metaplex
+      .nfts()
+      .pdas()
+      .metadata({ mint: rewardPoints })
+
+    await program.methods
+      .initRewardPoints(
+        100,
+        rewardPointsDecimals,
+        new anchor.BN(1_000_000 * 10 ** rewardPointsDecimals),
+        nonTransferable,
+        testMetadata.uri,
+        testMetadata.name,
+        testMetadata.symbol
+      )
+      .accounts({
+        authority: authority.publicKey,
+        metadataAccount: rewardPointsMetadataPDA,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc()
+
+    return merchantPaymentDestination
+  }
+
+  // airdrop to an additional customer and mint them a loyalty nft
+  // member "usdc" token account pays the member mint price if one is set
+  async function createTestLoyaltyNft(
+    member: anchor.web3.Keypair,
+    memberUsdcTokenAccount: anchor.web3.PublicKey = null,
+    printEdition: boolean = false,
+    programmable: boolean = false
+  ) {
+    const airdropSig = await connection.requestAirdrop(
+      member.publicKey,
+      1 * anchor.web3.LAMPORTS_PER_SOL
+    )
+
+    const { blockhash, lastValidBlockHeight } =
+      await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction(
+      {
+        blockhash,
+        lastValidBlockHeight,
+        signature: airdropSig,
+      },
+      "confirmed"
+    )
+
+    const [nftMint] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("LOYALTY_NFT"),
+        merchantPDA.toBuffer(),
+        member.publicKey.toBuffer(),
+      ],
+      program.programId
+    )
+
+    // print edition number is the next member number
+    let editionMarker: anchor.web3.PublicKey = null
+    if (printEdition) {
+      const [memberTemplatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("MEMBER_TEMPLATE"), merchantPDA.toBuffer()],
+        program.programId
+      )
+      const template = await program.account.memberTemplate.fetch(
+        memberTemplatePDA
+      )
+      editionMarker = editionMarkerPDA(template.memberCount.toNumber() + 1)
+    }
+
+    const tokenAccount = await spl.getAssociatedTokenAddress(
+      nftMint,
+      member.publicKey
+    )
+
+    const tx = await program.methods
+      .createNftInCollection()
+      .accounts({
+        customer: member.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        loyaltyCollectionMint: loyaltyCollectionPDA,
+        collectionMetadataAccount: metaplex
+          .nfts()
+          .pdas()
+          .metadata({ mint: loyaltyCollectionPDA }),
+        collectionMasterEdition: metaplex
+          .nfts()
+          .pdas()
+          .masterEdition({ mint: loyaltyCollectionPDA }),
+        customerNftMint: nftMint,
+        metadataAccount: metaplex.nfts().pdas().metadata({ mint: nftMint }),
+        masterEdition: metaplex.nfts().pdas().masterEdition({ mint: nftMint }),
+        tokenAccount: tokenAccount,
+        useAuthorityRecord: useAuthorityRecordPDA(nftMint),
+        burner: burnerPDA,
+        paymentDestination: memberUsdcTokenAccount ? paymentDestination : null,
+        customerUsdcTokenAccount: memberUsdcTokenAccount,
+        memberMasterMint: printEdition ? memberMasterPDA : null,
+        memberMasterMetadata: printEdition
+          ? metaplex.nfts().pdas().metadata({ mint: memberMasterPDA })
+          : null,
+        memberMasterEdition: printEdition
+          ? metaplex.nfts().pdas().masterEdition({ mint: memberMasterPDA })
+          : null,
+        memberMasterTokenAccount: printEdition
+          ? await spl.getAssociatedTokenAddress(
+              memberMasterPDA,
+              loyaltyCollectionPDA,
+              true
+            )
+          : null,
+        editionMarker: editionMarker,
+        tokenRecord: programmable
+          ? tokenRecordPDA(nftMint, tokenAccount)
+          : null,
+        sysvarInstructions: programmable
+          ? anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY
+          : null,
+        authorizationRulesProgram: null,
+        authorizationRules: null,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .transaction()
+
+    await anchor.web3.sendAndConfirmTransaction(
+      connection,
+      new anchor.web3.Transaction().add(
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: programmable ? 800_000 : 400_000,
+        }),
+        tx
+      ),
+      [member]
+    )
+
+    return nftMint
+  }
+
+  // loyalty nft collection size
+  async function collectionSize() {
+    const accInfo = await connection.getAccountInfo(
+      metaplex.nfts().pdas().metadata({ mint: loyaltyCollectionPDA })
+    )
+    const metadata = Metadata.deserialize(accInfo.data, 0)
+    // @ts-ignore
+    return metadata[0].collectionDetails.size.toNumber()
+  }
+
   it("initialize merchant", async () => {
     const txSig = await program.methods
       .initMerchant()
@@ -154,9 +458,15 @@ describe("anchor-grizzly", () => {
       .metadata({ mint: rewardPointsPDA })
 
     const rewardPointsBasisPoints = 100
+    const maxPointsSupply = new anchor.BN(
+      1_000_000 * 10 ** rewardPointsDecimals
+    )
     const txSig = await program.methods
       .initRewardPoints(
         rewardPointsBasisPoints,
+        rewardPointsDecimals,
+        maxPointsSupply,
+        false,
         testMetadata.uri,
         testMetadata.name,
         testMetadata.symbol
@@ -177,6 +487,11 @@ describe("anchor-grizzly", () => {
       merchantAccount.rewardPointsBasisPoints,
       rewardPointsBasisPoints
     )
+    assert.isTrue(merchantAccount.maxPointsSupply.eq(maxPointsSupply))
+
+    // check reward points mint decimals
+    const rewardPointsMint = await spl.getMint(connection, rewardPointsPDA)
+    assert.strictEqual(rewardPointsMint.decimals, rewardPointsDecimals)
 
     // check metadata account has expected data
     const accInfo = await connection.getAccountInfo(rewardPointsMetadataPDA)
@@ -196,6 +511,70 @@ describe("anchor-grizzly", () => {
     )
   })
 
+  it("initialize reward points mint again", async () => {
+    try {
+      await program.methods
+        .initRewardPoints(
+          200,
+          rewardPointsDecimals,
+          new anchor.BN(1),
+          false,
+          testMetadata.uri,
+          testMetadata.name,
+          testMetadata.symbol
+        )
+        .accounts({
+          authority: wallet.publicKey,
+          metadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: rewardPointsPDA }),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .rpc()
+      assert.fail("initialized reward points twice")
+    } catch (err) {
+      assert.strictEqual(
+        err.error.errorCode.code,
+        "RewardPointsAlreadyInitialized"
+      )
+    }
+  })
+
+  it("initialize reward points mint again, different decimals", async () => {
+    try {
+      await program.methods
+        .initRewardPoints(
+          200,
+          rewardPointsDecimals + 1,
+          new anchor.BN(1),
+          false,
+          testMetadata.uri,
+          testMetadata.name,
+          testMetadata.symbol
+        )
+        .accounts({
+          authority: wallet.publicKey,
+          metadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: rewardPointsPDA }),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .rpc()
+      assert.fail("initialized reward points twice")
+    } catch (err) {
+      assert.strictEqual(
+        err.error.errorCode.code,
+        "RewardPointsAlreadyInitialized"
+      )
+    }
+
+    // reward points mint decimals left unchanged
+    const rewardPointsMint = await spl.getMint(connection, rewardPointsPDA)
+    assert.strictEqual(rewardPointsMint.decimals, rewardPointsDecimals)
+  })
+
   it("transaction", async () => {
     const tx = await program.methods
       .transaction(new anchor.BN(10000))
@@ -204,8 +583,15 @@ describe("anchor-grizzly", () => {
         authority: wallet.publicKey,
         merchant: merchantPDA,
         paymentDestination: paymentDestination,
+        usdcMintPlaceholder: usdcPlaceholderMint,
         customerUsdcTokenAccount: customerUsdcTokenAccount,
         customerRewardTokenAccount: customerRewardTokenAccount,
+        customerState: customerStatePDA,
+        coalitionPointsMint: null,
+        coalitionMember: null,
+        customerCoalitionTokenAccount: null,
+        customerNftTokenAccount: null,
+        membership: null,
       })
       .transaction()
 
@@ -219,13 +605,21 @@ describe("anchor-grizzly", () => {
       0
     )
 
+    // 1% of 10000 "usdc" (0 decimals) is 100 reward points (6 decimals)
     assert.strictEqual(
       Number(
         (await connection.getTokenAccountBalance(customerRewardTokenAccount))
           .value.amount
       ),
-      100
+      100 * 10 ** rewardPointsDecimals
+    )
+
+    // check customer purchase streak started
+    const customerState = await program.account.customerState.fetch(
+      customerStatePDA
     )
+    assert.equal(customerState.currentStreak, 1)
+    assert.equal(customerState.longestStreak, 1)
 
     assert.strictEqual(
       Number(
@@ -247,10 +641,6 @@ describe("anchor-grizzly", () => {
       .pdas()
       .masterEdition({ mint: loyaltyCollectionPDA })
 
-    const loyaltyCollectionTokenAccount = await spl.getAssociatedTokenAddress(
-      loyaltyCollectionPDA,
-      wallet.publicKey
-    )
 
     // Instruction requires more compute units
     const modifyComputeUnits =
@@ -259,12 +649,16 @@ describe("anchor-grizzly", () => {
       })
 
     const loyaltyDiscountBasisPoints = 100
+    // 5% secondary sale royalty, inherited by member nfts
+    const sellerFeeBasisPoints = 500
     const tx = await program.methods
       .createCollectionNft(
         loyaltyDiscountBasisPoints,
         testMetadata.uri,
         testMetadata.name,
-        testMetadata.symbol
+        testMetadata.symbol,
+        sellerFeeBasisPoints,
+        [{ address: wallet.publicKey, share: 100 }]
       )
       .accounts({
         authority: wallet.publicKey,
@@ -272,7 +666,7 @@ describe("anchor-grizzly", () => {
         loyaltyCollectionMint: loyaltyCollectionPDA,
         metadataAccount: loyaltyCollectionMetadataPDA,
         masterEdition: loyaltyCollectionMasterEditionPDA,
-        tokenAccount: loyaltyCollectionTokenAccount,
+        collectionVault: collectionVaultPDA,
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       })
       .transaction()
@@ -321,7 +715,78 @@ describe("anchor-grizzly", () => {
 
     assert.isTrue(metadata[0].data.creators[0].address.equals(wallet.publicKey))
     assert.isTrue(metadata[0].data.creators[0].verified)
+    assert.strictEqual(
+      metadata[0].data.sellerFeeBasisPoints,
+      sellerFeeBasisPoints
+    )
     assert.isTrue(metadata[0].collectionDetails.__kind === "V1")
+
+    // collection nft held by merchant account's collection vault
+    const vault = await spl.getAccount(connection, collectionVaultPDA)
+    assert.isTrue(vault.owner.equals(merchantPDA))
+    assert.equal(Number(vault.amount), 1)
+  })
+
+  it("create collection nft again", async () => {
+    try {
+      await program.methods
+        .createCollectionNft(
+          200,
+          testMetadata.uri,
+          testMetadata.name,
+          testMetadata.symbol,
+          0,
+          [{ address: wallet.publicKey, share: 100 }]
+        )
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          loyaltyCollectionMint: loyaltyCollectionPDA,
+          metadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: loyaltyCollectionPDA }),
+          masterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: loyaltyCollectionPDA }),
+          collectionVault: collectionVaultPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .rpc()
+      assert.fail("created collection nft twice")
+    } catch (err) {
+      assert.strictEqual(
+        err.error.errorCode.code,
+        "LoyaltyCollectionAlreadyInitialized"
+      )
+    }
+
+    // loyalty discount left unchanged
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.equal(merchantAccount.loyaltyDiscountBasisPoints, 100)
+  })
+
+  it("update member template", async () => {
+    const [memberTemplatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MEMBER_TEMPLATE"), merchantPDA.toBuffer()],
+      program.programId
+    )
+
+    const txSig = await program.methods
+      .updateMemberTemplate(memberTemplate.baseUri, "Grizzly Member", "GRIZ")
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    const template = await program.account.memberTemplate.fetch(
+      memberTemplatePDA
+    )
+    assert.strictEqual(template.namePrefix, "Grizzly Member")
+    assert.strictEqual(template.memberCount.toNumber(), 0)
   })
 
   it("create nft in collection", async () => {
@@ -353,15 +818,11 @@ describe("anchor-grizzly", () => {
     // Instruction requires more compute units
     const modifyComputeUnits =
       anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
-        units: 300_000,
+        units: 400_000,
       })
 
     const tx = await program.methods
-      .createNftInCollection(
-        testMetadata.uri,
-        testMetadata.name,
-        testMetadata.symbol
-      )
+      .createNftInCollection()
       .accounts({
         customer: customer.publicKey,
         authority: wallet.publicKey,
@@ -373,6 +834,19 @@ describe("anchor-grizzly", () => {
         metadataAccount: customerNftMetadataPDA,
         masterEdition: customerNftMasterEditionPDA,
         tokenAccount: customerNftTokenAccount,
+        useAuthorityRecord: useAuthorityRecordPDA(customerNftPDA),
+        burner: burnerPDA,
+        paymentDestination: null,
+        customerUsdcTokenAccount: null,
+        memberMasterMint: null,
+        memberMasterMetadata: null,
+        memberMasterEdition: null,
+        memberMasterTokenAccount: null,
+        editionMarker: null,
+        tokenRecord: null,
+        sysvarInstructions: null,
+        authorizationRulesProgram: null,
+        authorizationRules: null,
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       })
       .transaction()
@@ -402,18 +876,29 @@ describe("anchor-grizzly", () => {
     const accInfo = await connection.getAccountInfo(customerNftMetadataPDA)
     const metadata = Metadata.deserialize(accInfo.data, 0)
 
+    // metadata generated from merchant template with member number
     assert.ok(
-      metadata[0].data.uri.startsWith(testMetadata.uri),
+      metadata[0].data.uri.startsWith(memberTemplate.baseUri + "1.json"),
       "URI in metadata does not start with expected URI"
     )
     assert.ok(
-      metadata[0].data.name.startsWith(testMetadata.name),
+      metadata[0].data.name.startsWith("Grizzly Member #1"),
       "Name in metadata does not start with expected name"
     )
     assert.ok(
-      metadata[0].data.symbol.startsWith(testMetadata.symbol),
+      metadata[0].data.symbol.startsWith("GRIZ"),
       "Symbol in metadata does not start with expected symbol"
     )
+
+    // royalty and creators inherited from collection, merchant account verified
+    assert.strictEqual(metadata[0].data.sellerFeeBasisPoints, 500)
+    const creators = metadata[0].data.creators
+    assert.isTrue(creators[0].address.equals(merchantPDA))
+    assert.isTrue(creators[0].verified)
+    assert.strictEqual(creators[0].share, 0)
+    assert.isTrue(creators[1].address.equals(wallet.publicKey))
+    assert.isFalse(creators[1].verified)
+    assert.strictEqual(creators[1].share, 100)
   })
 
   it("update reward points basis points", async () => {
@@ -477,4 +962,3167 @@ describe("anchor-grizzly", () => {
 
     assert.strictEqual(prebalance + 10000, postbalance)
   })
+
+  it("update voucher signer", async () => {
+    const txSig = await program.methods
+      .updateVoucherSigner(voucherSigner.publicKey)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    // check merchant account updated
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.isTrue(merchantAccount.voucherSigner.equals(voucherSigner.publicKey))
+  })
+
+  it("redeem voucher", async () => {
+    const points = new anchor.BN(500)
+    const nonce = new anchor.BN(1)
+    const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + 60 * 60)
+
+    // voucher nonce account, replay protection
+    const [voucherNoncePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("VOUCHER"),
+        merchantPDA.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )
+
+    // voucher payload signed off-chain by merchant's voucher signer
+    const message = Buffer.concat([
+      merchantPDA.toBuffer(),
+      customer.publicKey.toBuffer(),
+      points.toArrayLike(Buffer, "le", 8),
+      nonce.toArrayLike(Buffer, "le", 8),
+      expiry.toArrayLike(Buffer, "le", 8),
+    ])
+
+    const ed25519Instruction =
+      anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: voucherSigner.secretKey,
+        message: message,
+      })
+
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    const tx = await program.methods
+      .redeemVoucher(points, nonce, expiry)
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        voucherNonce: voucherNoncePDA,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .transaction()
+
+    const voucherTransaction = new anchor.web3.Transaction().add(
+      ed25519Instruction,
+      tx
+    )
+
+    await anchor.web3.sendAndConfirmTransaction(
+      connection,
+      voucherTransaction,
+      [customer]
+    )
+
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(prebalance + points.toNumber(), postbalance)
+
+    // redeeming the same voucher again fails, voucher nonce account exists
+    // compute budget instruction only makes the transaction signature unique
+    try {
+      await anchor.web3.sendAndConfirmTransaction(
+        connection,
+        new anchor.web3.Transaction().add(
+          anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+            units: 200_000,
+          }),
+          ed25519Instruction,
+          tx
+        ),
+        [customer]
+      )
+      assert.fail("voucher nonce was reused")
+    } catch (err) {
+      assert.include(
+        err.logs.join(),
+        "already in use",
+        "expected voucher nonce account already in use"
+      )
+    }
+  })
+
+  it("raise max points supply", async () => {
+    const maxPointsSupply = new anchor.BN(
+      2_000_000 * 10 ** rewardPointsDecimals
+    )
+    const txSig = await program.methods
+      .raiseMaxPointsSupply(maxPointsSupply)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    // check merchant account updated
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.isTrue(merchantAccount.maxPointsSupply.eq(maxPointsSupply))
+
+    // minting past the max supply fails
+    try {
+      await program.methods
+        .mintRewardPoints(maxPointsSupply)
+        .accounts({
+          authority: wallet.publicKey,
+          customer: customer.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+        })
+        .rpc()
+      assert.fail("minted past max points supply")
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "MaxPointsSupplyExceeded")
+    }
+  })
+
+  it("update reward points metadata", async () => {
+    const rewardPointsMetadataPDA = await metaplex
+      .nfts()
+      .pdas()
+      .metadata({ mint: rewardPointsPDA })
+
+    const updatedMetadata = {
+      uri: "https://arweave.net/updated",
+      name: "NEW NAME",
+      symbol: "NEW",
+    }
+
+    const txSig = await program.methods
+      .updateRewardPointsMetadata(
+        updatedMetadata.uri,
+        updatedMetadata.name,
+        updatedMetadata.symbol
+      )
+      .accounts({
+        authority: wallet.publicKey,
+        metadataAccount: rewardPointsMetadataPDA,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .rpc()
+
+    // check metadata account has updated data
+    const accInfo = await connection.getAccountInfo(rewardPointsMetadataPDA)
+    const metadata = Metadata.deserialize(accInfo.data, 0)
+
+    assert.ok(
+      metadata[0].data.uri.startsWith(updatedMetadata.uri),
+      "URI in metadata does not start with expected URI"
+    )
+    assert.ok(
+      metadata[0].data.name.startsWith(updatedMetadata.name),
+      "Name in metadata does not start with expected name"
+    )
+    assert.ok(
+      metadata[0].data.symbol.startsWith(updatedMetadata.symbol),
+      "Symbol in metadata does not start with expected symbol"
+    )
+  })
+
+  it("update streak tiers", async () => {
+    const streakTiers = [
+      { minStreakDays: 3, multiplierBasisPoints: 15000 },
+      { minStreakDays: 5, multiplierBasisPoints: 20000 },
+    ]
+    // purchases of at least 100 "usdc" count towards the streak
+    const minStreakPurchase = new anchor.BN(100)
+    const txSig = await program.methods
+      .updateStreakTiers(streakTiers, minStreakPurchase)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    // check merchant account updated, unused tiers zeroed
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.equal(merchantAccount.streakTiers[0].minStreakDays, 3)
+    assert.equal(merchantAccount.streakTiers[1].multiplierBasisPoints, 20000)
+    assert.equal(merchantAccount.streakTiers[2].minStreakDays, 0)
+    assert.isTrue(merchantAccount.minStreakPurchase.eq(minStreakPurchase))
+  })
+
+  it("update cash back over 100%", async () => {
+    try {
+      await program.methods
+        .updateCashBack(10001, new anchor.BN(0))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+      assert.fail("cash back set over 100%")
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "InvalidBasisPoints")
+    }
+  })
+
+  it("update cash back", async () => {
+    // 1 reward point redeems for 1 "usdc", minimum redemption of 1 reward point
+    const cashBackBasisPoints = 10000
+    const minRedemptionPoints = new anchor.BN(10 ** rewardPointsDecimals)
+    const txSig = await program.methods
+      .updateCashBack(cashBackBasisPoints, minRedemptionPoints)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    // check merchant account updated
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.equal(merchantAccount.cashBackBasisPoints, cashBackBasisPoints)
+    assert.isTrue(merchantAccount.minRedemptionPoints.eq(minRedemptionPoints))
+  })
+
+  it("fund points vault", async () => {
+    const txSig = await program.methods
+      .fundPointsVault(new anchor.BN(100))
+      .accounts({
+        authority: wallet.publicKey,
+        usdcMintPlaceholder: usdcPlaceholderMint,
+        paymentDestination: paymentDestination,
+        pointsVault: pointsVaultPDA,
+      })
+      .rpc()
+
+    assert.strictEqual(
+      Number(
+        (await connection.getTokenAccountBalance(pointsVaultPDA)).value.amount
+      ),
+      100
+    )
+  })
+
+  it("redeem points for cash", async () => {
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    const points = new anchor.BN(5 * 10 ** rewardPointsDecimals)
+    const tx = await program.methods
+      .redeemPointsForCash(points)
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        usdcMintPlaceholder: usdcPlaceholderMint,
+        pointsVault: pointsVaultPDA,
+        customerUsdcTokenAccount: customerUsdcTokenAccount,
+      })
+      .transaction()
+
+    await anchor.web3.sendAndConfirmTransaction(connection, tx, [customer])
+
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(prebalance - points.toNumber(), postbalance)
+
+    // 5 reward points paid out as 5 "usdc" from points vault
+    assert.strictEqual(
+      Number(
+        (await connection.getTokenAccountBalance(customerUsdcTokenAccount))
+          .value.amount
+      ),
+      5
+    )
+    assert.strictEqual(
+      Number(
+        (await connection.getTokenAccountBalance(pointsVaultPDA)).value.amount
+      ),
+      95
+    )
+  })
+
+  it("withdraw points vault", async () => {
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(paymentDestination)).value
+        .amount
+    )
+
+    const txSig = await program.methods
+      .withdrawPointsVault(new anchor.BN(45))
+      .accounts({
+        authority: wallet.publicKey,
+        paymentDestination: paymentDestination,
+        pointsVault: pointsVaultPDA,
+      })
+      .rpc()
+
+    assert.strictEqual(
+      Number(
+        (await connection.getTokenAccountBalance(pointsVaultPDA)).value.amount
+      ),
+      50
+    )
+    assert.strictEqual(
+      Number(
+        (await connection.getTokenAccountBalance(paymentDestination)).value
+          .amount
+      ),
+      prebalance + 45
+    )
+  })
+
+  it("update staking boost over 100%", async () => {
+    try {
+      await program.methods
+        .updateStaking(10001, 1000, new anchor.BN(0))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+      assert.fail("stake boost set over 100%")
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "InvalidBasisPoints")
+    }
+  })
+
+  it("update staking", async () => {
+    // 50% boost while staked, 10% penalty for unstaking early,
+    // boost requires a stake of at least 5 reward points
+    const minStakeAmount = new anchor.BN(5 * 10 ** rewardPointsDecimals)
+    const txSig = await program.methods
+      .updateStaking(5000, 1000, minStakeAmount)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.equal(merchantAccount.stakeBoostBasisPoints, 5000)
+    assert.equal(merchantAccount.earlyUnstakePenaltyBasisPoints, 1000)
+    assert.isTrue(merchantAccount.minStakeAmount.eq(minStakeAmount))
+  })
+
+  it("stake points below min stake amount", async () => {
+    try {
+      await program.methods
+        .stakePoints(
+          new anchor.BN(10 ** rewardPointsDecimals),
+          new anchor.BN(60 * 60)
+        )
+        .accounts({
+          customer: customer.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+          stakeVault: stakeVaultPDA,
+          customerState: customerStatePDA,
+        })
+        .signers([customer])
+        .rpc()
+      assert.fail("staked below min stake amount")
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "StakeBelowMinimum")
+    }
+  })
+
+  it("stake points", async () => {
+    const amount = new anchor.BN(10 * 10 ** rewardPointsDecimals)
+    const txSig = await program.methods
+      .stakePoints(amount, new anchor.BN(60 * 60))
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        stakeVault: stakeVaultPDA,
+        customerState: customerStatePDA,
+      })
+      .signers([customer])
+      .rpc()
+
+    const customerState = await program.account.customerState.fetch(
+      customerStatePDA
+    )
+    assert.isTrue(customerState.stakedAmount.eq(amount))
+    assert.strictEqual(
+      Number(
+        (await connection.getTokenAccountBalance(stakeVaultPDA)).value.amount
+      ),
+      amount.toNumber()
+    )
+  })
+
+  it("transaction with stake boost", async () => {
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    const tx = await program.methods
+      .transaction(new anchor.BN(5))
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        paymentDestination: paymentDestination,
+        usdcMintPlaceholder: usdcPlaceholderMint,
+        customerUsdcTokenAccount: customerUsdcTokenAccount,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        customerState: customerStatePDA,
+        coalitionPointsMint: null,
+        coalitionMember: null,
+        customerCoalitionTokenAccount: null,
+        customerNftTokenAccount: null,
+        membership: null,
+      })
+      .transaction()
+
+    await anchor.web3.sendAndConfirmTransaction(connection, tx, [customer])
+
+    // 2% of 5 "usdc" is 0.1 reward points, plus 50% stake boost
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(postbalance - prebalance, 150_000)
+  })
+
+  it("unstake points", async () => {
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    const txSig = await program.methods
+      .unstakePoints()
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        stakeVault: stakeVaultPDA,
+        customerState: customerStatePDA,
+      })
+      .signers([customer])
+      .rpc()
+
+    // unstaked before unlock, 10% penalty burned
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(postbalance - prebalance, 9 * 10 ** rewardPointsDecimals)
+
+    const customerState = await program.account.customerState.fetch(
+      customerStatePDA
+    )
+    assert.strictEqual(customerState.stakedAmount.toNumber(), 0)
+  })
+
+  it("early unstake penalty rounds up", async () => {
+    const stakeAccounts = {
+      customer: customer.publicKey,
+      authority: wallet.publicKey,
+      merchant: merchantPDA,
+      customerRewardTokenAccount: customerRewardTokenAccount,
+      stakeVault: stakeVaultPDA,
+      customerState: customerStatePDA,
+    }
+
+    const amount = new anchor.BN(5 * 10 ** rewardPointsDecimals + 1)
+    await program.methods
+      .stakePoints(amount, new anchor.BN(60 * 60))
+      .accounts(stakeAccounts)
+      .signers([customer])
+      .rpc()
+
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    await program.methods
+      .unstakePoints()
+      .accounts(stakeAccounts)
+      .signers([customer])
+      .rpc()
+
+    // 10% of 5_000_001 is 500_000.1, penalty of 500_001 burned
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(postbalance - prebalance, 4_500_000)
+  })
+
+  it("early unstake penalty fixed when staked", async () => {
+    const stakeAccounts = {
+      customer: customer.publicKey,
+      authority: wallet.publicKey,
+      merchant: merchantPDA,
+      customerRewardTokenAccount: customerRewardTokenAccount,
+      stakeVault: stakeVaultPDA,
+      customerState: customerStatePDA,
+    }
+
+    const amount = new anchor.BN(5 * 10 ** rewardPointsDecimals)
+    await program.methods
+      .stakePoints(amount, new anchor.BN(60 * 60))
+      .accounts(stakeAccounts)
+      .signers([customer])
+      .rpc()
+
+    const customerState = await program.account.customerState.fetch(
+      customerStatePDA
+    )
+    assert.strictEqual(customerState.stakeBoostBasisPoints, 5000)
+    assert.strictEqual(customerState.stakePenaltyBasisPoints, 1000)
+
+    // merchant raises the penalty to 50% after the customer staked
+    await program.methods
+      .updateStaking(5000, 5000, new anchor.BN(5 * 10 ** rewardPointsDecimals))
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    await program.methods
+      .unstakePoints()
+      .accounts(stakeAccounts)
+      .signers([customer])
+      .rpc()
+
+    // 10% penalty accepted when staked is burned, not 50%
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(postbalance - prebalance, 4_500_000)
+
+    await program.methods
+      .updateStaking(5000, 1000, new anchor.BN(5 * 10 ** rewardPointsDecimals))
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+  })
+
+  it("update points vesting", async () => {
+    const txSig = await program.methods
+      .updatePointsVesting(new anchor.BN(1))
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    const merchantAccount = await program.account.merchantState.fetch(
+      merchantPDA
+    )
+    assert.strictEqual(merchantAccount.pointsVestingPeriod.toNumber(), 1)
+  })
+
+  it("vest points", async () => {
+    // mint "usdc" tokens to customer
+    await spl.mintTo(
+      connection,
+      wallet.payer,
+      usdcPlaceholderMint,
+      customerUsdcTokenAccount,
+      wallet.payer,
+      100
+    )
+
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    const tx = await program.methods
+      .transaction(new anchor.BN(100))
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        paymentDestination: paymentDestination,
+        usdcMintPlaceholder: usdcPlaceholderMint,
+        customerUsdcTokenAccount: customerUsdcTokenAccount,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        customerState: customerStatePDA,
+        coalitionPointsMint: null,
+        coalitionMember: null,
+        customerCoalitionTokenAccount: null,
+        customerNftTokenAccount: null,
+        membership: null,
+      })
+      .transaction()
+
+    await anchor.web3.sendAndConfirmTransaction(connection, tx, [customer])
+
+    // 2% of 100 "usdc" credited as pending reward points
+    const pendingPoints = 2 * 10 ** rewardPointsDecimals
+    let customerState = await program.account.customerState.fetch(
+      customerStatePDA
+    )
+    assert.strictEqual(customerState.pendingPoints.toNumber(), pendingPoints)
+
+    // wait for vesting period to pass
+    await new Promise((resolve) => setTimeout(resolve, 3000))
+
+    // vested by wallet on customer's behalf
+    const txSig = await program.methods
+      .vestPoints()
+      .accounts({
+        payer: wallet.publicKey,
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        customerState: customerStatePDA,
+      })
+      .rpc()
+
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(postbalance - prebalance, pendingPoints)
+
+    customerState = await program.account.customerState.fetch(
+      customerStatePDA
+    )
+    assert.strictEqual(customerState.pendingPoints.toNumber(), 0)
+
+    // reset vesting period, later checkouts mint immediately
+    await program.methods
+      .updatePointsVesting(new anchor.BN(0))
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+  })
+
+  it("update loyalty levels", async () => {
+    const [loyaltyLevelsPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("LOYALTY_LEVELS"), merchantPDA.toBuffer()],
+      program.programId
+    )
+
+    const txSig = await program.methods
+      .updateLoyaltyLevels([
+        {
+          uri: testMetadata.uri + "?level=1",
+          upgradePrice: new anchor.BN(10 ** rewardPointsDecimals),
+        },
+      ])
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc()
+
+    const loyaltyLevels = await program.account.loyaltyLevels.fetch(
+      loyaltyLevelsPDA
+    )
+    assert.strictEqual(loyaltyLevels.levels.length, 1)
+  })
+
+  it("update loyalty levels, removing levels", async () => {
+    try {
+      await program.methods
+        .updateLoyaltyLevels([])
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+      assert.fail("loyalty levels removed")
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "LoyaltyLevelsRemoved")
+    }
+  })
+
+  it("upgrade loyalty nft", async () => {
+    const customerNftMetadataPDA = await metaplex
+      .nfts()
+      .pdas()
+      .metadata({ mint: customerNftPDA })
+
+    const customerNftTokenAccount = await spl.getAssociatedTokenAddress(
+      customerNftPDA,
+      customer.publicKey
+    )
+
+    const [membershipPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MEMBERSHIP"), customerNftPDA.toBuffer()],
+      program.programId
+    )
+
+    const prebalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+
+    const tx = await program.methods
+      .upgradeLoyaltyNft()
+      .accounts({
+        customer: customer.publicKey,
+        authority: wallet.publicKey,
+        merchant: merchantPDA,
+        customerRewardTokenAccount: customerRewardTokenAccount,
+        customerNftMint: customerNftPDA,
+        customerNftTokenAccount: customerNftTokenAccount,
+        metadataAccount: customerNftMetadataPDA,
+        sysvarInstructions: null,
+        authorizationRulesProgram: null,
+        authorizationRules: null,
+        systemProgram: null,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .transaction()
+
+    await anchor.web3.sendAndConfirmTransaction(connection, tx, [customer])
+
+    // upgrade price burned
+    const postbalance = Number(
+      (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+        .value.amount
+    )
+    assert.strictEqual(prebalance - postbalance, 10 ** rewardPointsDecimals)
+
+    const membership = await program.account.membership.fetch(membershipPDA)
+    assert.strictEqual(membership.level, 1)
+
+    // uri moved to level 1, collection still verified
+    const accInfo = await connection.getAccountInfo(customerNftMetadataPDA)
+    const metadata = Metadata.deserialize(accInfo.data, 0)
+    assert.ok(metadata[0].data.uri.startsWith(testMetadata.uri + "?level=1"))
+    assert.isTrue(metadata[0].collection.verified)
+
+    // no level above level 1
+    try {
+      await program.methods
+        .upgradeLoyaltyNft()
+        .accounts({
+          customer: customer.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+          customerNftMint: customerNftPDA,
+          customerNftTokenAccount: customerNftTokenAccount,
+          metadataAccount: customerNftMetadataPDA,
+          sysvarInstructions: null,
+          authorizationRulesProgram: null,
+          authorizationRules: null,
+          systemProgram: null,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([customer])
+        .rpc()
+      assert.fail("upgraded past max level")
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "MaxLoyaltyLevelReached")
+    }
+  })
+
+  describe("coalition", () => {
+    // second merchant joining a coalition governed by wallet
+    const coalitionMerchant = anchor.web3.Keypair.generate()
+
+    const [coalitionPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("COALITION"), wallet.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [coalitionPointsPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("COALITION_POINTS"), coalitionPDA.toBuffer()],
+      program.programId
+    )
+
+    const [coalitionMerchantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MERCHANT"), coalitionMerchant.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [coalitionMemberPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("COALITION_MEMBER"),
+        coalitionPDA.toBuffer(),
+        coalitionMerchantPDA.toBuffer(),
+      ],
+      program.programId
+    )
+
+    const [coalitionMerchantRewardPointsPDA] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("REWARD_POINTS"), coalitionMerchantPDA.toBuffer()],
+        program.programId
+      )
+
+    let coalitionMerchantPaymentDestination: anchor.web3.PublicKey
+    let customerCoalitionTokenAccount: anchor.web3.PublicKey
+
+    before(async () => {
+      coalitionMerchantPaymentDestination = await initTestMerchant(
+        coalitionMerchant
+      )
+
+      customerCoalitionTokenAccount = await spl.getAssociatedTokenAddress(
+        coalitionPointsPDA,
+        customer.publicKey
+      )
+
+      // mint "usdc" tokens to customer
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        customerUsdcTokenAccount,
+        wallet.payer,
+        10000
+      )
+    })
+
+    it("initialize coalition, too many decimals", async () => {
+      try {
+        await program.methods
+          .initCoalition(19)
+          .accounts({
+            authority: wallet.publicKey,
+            coalition: coalitionPDA,
+            coalitionPointsMint: coalitionPointsPDA,
+          })
+          .rpc()
+        assert.fail("coalition points mint created with 19 decimals")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "InvalidPointsDecimals")
+      }
+    })
+
+    it("initialize coalition", async () => {
+      const txSig = await program.methods
+        .initCoalition(rewardPointsDecimals)
+        .accounts({
+          authority: wallet.publicKey,
+          coalition: coalitionPDA,
+          coalitionPointsMint: coalitionPointsPDA,
+        })
+        .rpc()
+
+      const coalitionAccount = await program.account.coalition.fetch(
+        coalitionPDA
+      )
+      assert.isTrue(coalitionAccount.authority.equals(wallet.publicKey))
+      assert.isTrue(coalitionAccount.pointsMint.equals(coalitionPointsPDA))
+    })
+
+    it("admit coalition merchant", async () => {
+      const txSig = await program.methods
+        .admitCoalitionMerchant()
+        .accounts({
+          coalitionAuthority: wallet.publicKey,
+          authority: coalitionMerchant.publicKey,
+          merchant: coalitionMerchantPDA,
+          coalition: coalitionPDA,
+          coalitionMember: coalitionMemberPDA,
+        })
+        .signers([coalitionMerchant])
+        .rpc()
+
+      const merchantAccount = await program.account.merchantState.fetch(
+        coalitionMerchantPDA
+      )
+      assert.isTrue(merchantAccount.coalition.equals(coalitionPDA))
+
+      const coalitionAccount = await program.account.coalition.fetch(
+        coalitionPDA
+      )
+      assert.equal(coalitionAccount.memberCount, 1)
+    })
+
+    it("update points vesting for coalition merchant", async () => {
+      try {
+        await program.methods
+          .updatePointsVesting(new anchor.BN(60))
+          .accounts({
+            authority: coalitionMerchant.publicKey,
+            merchant: coalitionMerchantPDA,
+          })
+          .signers([coalitionMerchant])
+          .rpc()
+        assert.fail("points vesting set for coalition merchant")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "VestingUnavailableForCoalition"
+        )
+      }
+    })
+
+    it("transaction mints coalition points", async () => {
+      const customerRewardTokenAccount = await spl.getAssociatedTokenAddress(
+        coalitionMerchantRewardPointsPDA,
+        customer.publicKey
+      )
+
+      const [customerStatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("CUSTOMER"),
+          coalitionMerchantPDA.toBuffer(),
+          customer.publicKey.toBuffer(),
+        ],
+        program.programId
+      )
+
+      const tx = await program.methods
+        .transaction(new anchor.BN(10000))
+        .accounts({
+          customer: customer.publicKey,
+          authority: coalitionMerchant.publicKey,
+          merchant: coalitionMerchantPDA,
+          paymentDestination: coalitionMerchantPaymentDestination,
+          usdcMintPlaceholder: usdcPlaceholderMint,
+          customerUsdcTokenAccount: customerUsdcTokenAccount,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+          customerState: customerStatePDA,
+          coalitionPointsMint: coalitionPointsPDA,
+          coalitionMember: coalitionMemberPDA,
+          customerCoalitionTokenAccount: customerCoalitionTokenAccount,
+          customerNftTokenAccount: null,
+          membership: null,
+        })
+        .transaction()
+
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [customer])
+
+      // coalition points minted instead of merchant reward points
+      assert.strictEqual(
+        Number(
+          (
+            await connection.getTokenAccountBalance(
+              customerCoalitionTokenAccount
+            )
+          ).value.amount
+        ),
+        100 * 10 ** rewardPointsDecimals
+      )
+
+      const coalitionMember = await program.account.coalitionMember.fetch(
+        coalitionMemberPDA
+      )
+      assert.strictEqual(
+        coalitionMember.pointsIssued.toNumber(),
+        100 * 10 ** rewardPointsDecimals
+      )
+    })
+
+    it("redeem coalition points", async () => {
+      const points = new anchor.BN(40 * 10 ** rewardPointsDecimals)
+      const txSig = await program.methods
+        .redeemCoalitionPoints(points)
+        .accounts({
+          customer: customer.publicKey,
+          authority: coalitionMerchant.publicKey,
+          merchant: coalitionMerchantPDA,
+          coalitionPointsMint: coalitionPointsPDA,
+          coalitionMember: coalitionMemberPDA,
+          customerCoalitionTokenAccount: customerCoalitionTokenAccount,
+        })
+        .signers([customer, coalitionMerchant])
+        .rpc()
+
+      const coalitionMember = await program.account.coalitionMember.fetch(
+        coalitionMemberPDA
+      )
+      assert.isTrue(coalitionMember.pointsRedeemed.eq(points))
+      assert.strictEqual(
+        Number(
+          (
+            await connection.getTokenAccountBalance(
+              customerCoalitionTokenAccount
+            )
+          ).value.amount
+        ),
+        60 * 10 ** rewardPointsDecimals
+      )
+    })
+  })
+
+  describe("exchange rates", () => {
+    // second merchant accepting the first merchant's reward points
+    const exchangeMerchant = anchor.web3.Keypair.generate()
+
+    const [exchangeMerchantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MERCHANT"), exchangeMerchant.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [exchangeRewardPointsPDA] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("REWARD_POINTS"), exchangeMerchantPDA.toBuffer()],
+        program.programId
+      )
+
+    const [exchangeRatePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("EXCHANGE_RATE"),
+        exchangeMerchantPDA.toBuffer(),
+        merchantPDA.toBuffer(),
+      ],
+      program.programId
+    )
+
+    const dailyVolumeCap = new anchor.BN(20 * 10 ** rewardPointsDecimals)
+
+    before(async () => {
+      await initTestMerchant(exchangeMerchant)
+    })
+
+    it("set new exchange rate of 0", async () => {
+      try {
+        await program.methods
+          .setExchangeRate(0, dailyVolumeCap)
+          .accounts({
+            authority: exchangeMerchant.publicKey,
+            merchant: exchangeMerchantPDA,
+            sourceMerchant: merchantPDA,
+            exchangeRate: exchangeRatePDA,
+          })
+          .signers([exchangeMerchant])
+          .rpc()
+        assert.fail("new exchange rate of 0 published")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "InvalidExchangeRate")
+      }
+    })
+
+    it("set exchange rate", async () => {
+      // accept first merchant's reward points at 0.5x
+      const txSig = await program.methods
+        .setExchangeRate(5000, dailyVolumeCap)
+        .accounts({
+          authority: exchangeMerchant.publicKey,
+          merchant: exchangeMerchantPDA,
+          sourceMerchant: merchantPDA,
+          exchangeRate: exchangeRatePDA,
+        })
+        .signers([exchangeMerchant])
+        .rpc()
+
+      const exchangeRate = await program.account.exchangeRate.fetch(
+        exchangeRatePDA
+      )
+      assert.equal(exchangeRate.rateBasisPoints, 5000)
+      assert.isTrue(exchangeRate.dailyVolumeCap.eq(dailyVolumeCap))
+    })
+
+    it("swap points", async () => {
+      const customerDestinationTokenAccount =
+        await spl.getAssociatedTokenAddress(
+          exchangeRewardPointsPDA,
+          customer.publicKey
+        )
+
+      const swapAccounts = {
+        customer: customer.publicKey,
+        sourceMerchant: merchantPDA,
+        destinationMerchant: exchangeMerchantPDA,
+        exchangeRate: exchangeRatePDA,
+        sourcePointsMint: rewardPointsPDA,
+        destinationPointsMint: exchangeRewardPointsPDA,
+        customerSourceTokenAccount: customerRewardTokenAccount,
+        customerDestinationTokenAccount: customerDestinationTokenAccount,
+      }
+
+      const prebalance = Number(
+        (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+          .value.amount
+      )
+
+      const amount = new anchor.BN(10 * 10 ** rewardPointsDecimals)
+      const txSig = await program.methods
+        .swapPoints(amount)
+        .accounts(swapAccounts)
+        .signers([customer])
+        .rpc()
+
+      const postbalance = Number(
+        (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+          .value.amount
+      )
+      assert.strictEqual(prebalance - amount.toNumber(), postbalance)
+
+      // 10 reward points swapped at 0.5x
+      assert.strictEqual(
+        Number(
+          (
+            await connection.getTokenAccountBalance(
+              customerDestinationTokenAccount
+            )
+          ).value.amount
+        ),
+        5 * 10 ** rewardPointsDecimals
+      )
+
+      // swapping past the daily volume cap fails
+      try {
+        await program.methods
+          .swapPoints(dailyVolumeCap)
+          .accounts(swapAccounts)
+          .signers([customer])
+          .rpc()
+        assert.fail("swapped past daily volume cap")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "ExchangeVolumeCapExceeded"
+        )
+      }
+    })
+
+    it("swap points converting to 0 destination points", async () => {
+      try {
+        // 1 source reward point unit at 0.5x rounds down to 0
+        await program.methods
+          .swapPoints(new anchor.BN(1))
+          .accounts({
+            customer: customer.publicKey,
+            sourceMerchant: merchantPDA,
+            destinationMerchant: exchangeMerchantPDA,
+            exchangeRate: exchangeRatePDA,
+            sourcePointsMint: rewardPointsPDA,
+            destinationPointsMint: exchangeRewardPointsPDA,
+            customerSourceTokenAccount: customerRewardTokenAccount,
+            customerDestinationTokenAccount:
+              await spl.getAssociatedTokenAddress(
+                exchangeRewardPointsPDA,
+                customer.publicKey
+              ),
+          })
+          .signers([customer])
+          .rpc()
+        assert.fail("swapped points for 0 destination points")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "SwapAmountTooSmall")
+      }
+    })
+
+    it("disable exchange rate", async () => {
+      const txSig = await program.methods
+        .setExchangeRate(0, dailyVolumeCap)
+        .accounts({
+          authority: exchangeMerchant.publicKey,
+          merchant: exchangeMerchantPDA,
+          sourceMerchant: merchantPDA,
+          exchangeRate: exchangeRatePDA,
+        })
+        .signers([exchangeMerchant])
+        .rpc()
+
+      const exchangeRate = await program.account.exchangeRate.fetch(
+        exchangeRatePDA
+      )
+      assert.equal(exchangeRate.rateBasisPoints, 0)
+    })
+  })
+
+  describe("reissue loyalty nft", () => {
+    const member = anchor.web3.Keypair.generate()
+    let burnedNftMint: anchor.web3.PublicKey
+
+    const [loyaltyLevelsPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("LOYALTY_LEVELS"), merchantPDA.toBuffer()],
+      program.programId
+    )
+
+    before(async () => {
+      burnedNftMint = await createTestLoyaltyNft(member)
+
+      // upgrade member nft to level 1, reissued nft keeps the level uri
+      const memberRewardTokenAccount = await spl.getAssociatedTokenAddress(
+        rewardPointsPDA,
+        member.publicKey
+      )
+      await program.methods
+        .mintRewardPoints(new anchor.BN(10 ** rewardPointsDecimals))
+        .accounts({
+          authority: wallet.publicKey,
+          customer: member.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: memberRewardTokenAccount,
+        })
+        .rpc()
+
+      await program.methods
+        .upgradeLoyaltyNft()
+        .accounts({
+          customer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: memberRewardTokenAccount,
+          customerNftMint: burnedNftMint,
+          customerNftTokenAccount: await spl.getAssociatedTokenAddress(
+            burnedNftMint,
+            member.publicKey
+          ),
+          metadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: burnedNftMint }),
+          sysvarInstructions: null,
+          authorizationRulesProgram: null,
+          authorizationRules: null,
+          systemProgram: null,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([member])
+        .rpc()
+    })
+
+    it("reissue burned loyalty nft", async () => {
+      const sizeBefore = await collectionSize()
+
+      // customer burns loyalty nft, metadata and master edition are left over
+      const burnedTokenAccount = await spl.getAssociatedTokenAddress(
+        burnedNftMint,
+        member.publicKey
+      )
+      await spl.burn(
+        connection,
+        member,
+        burnedTokenAccount,
+        burnedNftMint,
+        member,
+        1
+      )
+
+      const version = 1
+      const versionBuffer = Buffer.alloc(4)
+      versionBuffer.writeUInt32LE(version)
+      const [reissuedNftMint] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("LOYALTY_NFT"),
+          merchantPDA.toBuffer(),
+          member.publicKey.toBuffer(),
+          versionBuffer,
+        ],
+        program.programId
+      )
+
+      const burnedMetadataPDA = await metaplex
+        .nfts()
+        .pdas()
+        .metadata({ mint: burnedNftMint })
+
+      const tx = await program.methods
+        .reissueLoyaltyNft(version)
+        .accounts({
+          customer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          collectionMetadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: loyaltyCollectionPDA }),
+          collectionMasterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: loyaltyCollectionPDA }),
+          burnedNftMint: burnedNftMint,
+          burnedMetadataAccount: burnedMetadataPDA,
+          customerNftMint: reissuedNftMint,
+          metadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: reissuedNftMint }),
+          masterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: reissuedNftMint }),
+          tokenAccount: await spl.getAssociatedTokenAddress(
+            reissuedNftMint,
+            member.publicKey
+          ),
+          useAuthorityRecord: useAuthorityRecordPDA(reissuedNftMint),
+          burner: burnerPDA,
+          loyaltyLevels: loyaltyLevelsPDA,
+          tokenRecord: null,
+          sysvarInstructions: null,
+          authorizationRulesProgram: null,
+          authorizationRules: null,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .transaction()
+
+      await anchor.web3.sendAndConfirmTransaction(
+        connection,
+        new anchor.web3.Transaction().add(
+          anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+            units: 600_000,
+          }),
+          tx
+        ),
+        [member]
+      )
+
+      // reissued nft keeps the level 1 uri of the burned nft
+      const reissuedMetadataAccInfo = await connection.getAccountInfo(
+        await metaplex.nfts().pdas().metadata({ mint: reissuedNftMint })
+      )
+      const reissuedMetadata = Metadata.deserialize(
+        reissuedMetadataAccInfo.data,
+        0
+      )
+      assert.ok(
+        reissuedMetadata[0].data.uri.startsWith(testMetadata.uri + "?level=1")
+      )
+
+      // burned nft unverified, reissued nft verified, collection size unchanged
+      assert.strictEqual(await collectionSize(), sizeBefore)
+
+      const burnedAccInfo = await connection.getAccountInfo(burnedMetadataPDA)
+      const burnedMetadata = Metadata.deserialize(burnedAccInfo.data, 0)
+      assert.isFalse(burnedMetadata[0].collection.verified)
+
+      const reissuedTokenAccount = await spl.getAccount(
+        connection,
+        await spl.getAssociatedTokenAddress(reissuedNftMint, member.publicKey)
+      )
+      assert.strictEqual(Number(reissuedTokenAccount.amount), 1)
+    })
+  })
+
+  describe("non-transferable reward points", () => {
+    const soulboundMerchant = anchor.web3.Keypair.generate()
+
+    const [soulboundMerchantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MERCHANT"), soulboundMerchant.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [soulboundRewardPointsPDA] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("REWARD_POINTS"), soulboundMerchantPDA.toBuffer()],
+        program.programId
+      )
+
+    before(async () => {
+      await initTestMerchant(soulboundMerchant, true)
+    })
+
+    it("mint non-transferable reward points", async () => {
+      const customerSoulboundTokenAccount =
+        await spl.getAssociatedTokenAddress(
+          soulboundRewardPointsPDA,
+          customer.publicKey
+        )
+
+      const txSig = await program.methods
+        .mintRewardPoints(new anchor.BN(10000))
+        .accounts({
+          authority: soulboundMerchant.publicKey,
+          customer: customer.publicKey,
+          merchant: soulboundMerchantPDA,
+          customerRewardTokenAccount: customerSoulboundTokenAccount,
+        })
+        .signers([soulboundMerchant])
+        .rpc()
+
+      // customer token account frozen after minting
+      const tokenAccount = await spl.getAccount(
+        connection,
+        customerSoulboundTokenAccount
+      )
+      assert.strictEqual(Number(tokenAccount.amount), 10000)
+      assert.isTrue(tokenAccount.isFrozen)
+
+      // customer cannot transfer reward points
+      const destination = await spl.getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        soulboundRewardPointsPDA,
+        wallet.publicKey
+      )
+      try {
+        await spl.transfer(
+          connection,
+          wallet.payer,
+          customerSoulboundTokenAccount,
+          destination.address,
+          customer,
+          1
+        )
+        assert.fail("transferred non-transferable reward points")
+      } catch (err) {
+        assert.notEqual(
+          err.message,
+          "transferred non-transferable reward points"
+        )
+      }
+    })
+  })
+
+  describe("soulbound loyalty nft", () => {
+    const member = anchor.web3.Keypair.generate()
+    let nftMint: anchor.web3.PublicKey
+    let nftTokenAccount: anchor.web3.PublicKey
+
+    before(async () => {
+      await program.methods
+        .updateSoulboundLoyaltyNft(true)
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+
+      nftMint = await createTestLoyaltyNft(member)
+      nftTokenAccount = await spl.getAssociatedTokenAddress(
+        nftMint,
+        member.publicKey
+      )
+    })
+
+    after(async () => {
+      await program.methods
+        .updateSoulboundLoyaltyNft(false)
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    it("mint soulbound loyalty nft", async () => {
+      const tokenAccount = await spl.getAccount(connection, nftTokenAccount)
+      assert.isTrue(tokenAccount.isFrozen)
+
+      // transfer out of frozen token account fails
+      try {
+        await spl.transfer(
+          connection,
+          member,
+          nftTokenAccount,
+          await spl.createAssociatedTokenAccount(
+            connection,
+            member,
+            nftMint,
+            wallet.publicKey
+          ),
+          member,
+          1
+        )
+        assert.fail("transferred soulbound loyalty nft")
+      } catch (err) {
+        assert.notEqual(err.message, "transferred soulbound loyalty nft")
+      }
+    })
+
+    it("unlock loyalty nft", async () => {
+      const txSig = await program.methods
+        .unlockLoyaltyNft()
+        .accounts({
+          signer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          nftMint: nftMint,
+          tokenAccount: nftTokenAccount,
+          metadataAccount: metaplex.nfts().pdas().metadata({ mint: nftMint }),
+          masterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: nftMint }),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([member])
+        .rpc()
+
+      const tokenAccount = await spl.getAccount(connection, nftTokenAccount)
+      assert.isFalse(tokenAccount.isFrozen)
+    })
+  })
+
+  describe("revoke membership", () => {
+    const member = anchor.web3.Keypair.generate()
+    let nftMint: anchor.web3.PublicKey
+    let nftTokenAccount: anchor.web3.PublicKey
+    let membershipPDA: anchor.web3.PublicKey
+    let memberUsdcTokenAccount: anchor.web3.PublicKey
+
+    before(async () => {
+      nftMint = await createTestLoyaltyNft(member)
+      nftTokenAccount = await spl.getAssociatedTokenAddress(
+        nftMint,
+        member.publicKey
+      )
+      ;[membershipPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("MEMBERSHIP"), nftMint.toBuffer()],
+        program.programId
+      )
+
+      memberUsdcTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        member,
+        usdcPlaceholderMint,
+        member.publicKey
+      )
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        memberUsdcTokenAccount,
+        wallet.payer,
+        20000
+      )
+    })
+
+    // checkout presenting member loyalty nft
+    async function memberTransaction(amount: number) {
+      const tx = await program.methods
+        .transaction(new anchor.BN(amount))
+        .accounts({
+          customer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          paymentDestination: paymentDestination,
+          usdcMintPlaceholder: usdcPlaceholderMint,
+          customerUsdcTokenAccount: memberUsdcTokenAccount,
+          customerRewardTokenAccount: await spl.getAssociatedTokenAddress(
+            rewardPointsPDA,
+            member.publicKey
+          ),
+          coalitionPointsMint: null,
+          coalitionMember: null,
+          customerCoalitionTokenAccount: null,
+          customerNftTokenAccount: nftTokenAccount,
+          membership: membershipPDA,
+        })
+        .transaction()
+
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [member])
+    }
+
+    it("transaction with loyalty discount", async () => {
+      const prebalance = Number(
+        (await connection.getTokenAccountBalance(memberUsdcTokenAccount)).value
+          .amount
+      )
+
+      await memberTransaction(10000)
+
+      // 2% loyalty discount
+      const postbalance = Number(
+        (await connection.getTokenAccountBalance(memberUsdcTokenAccount)).value
+          .amount
+      )
+      assert.strictEqual(prebalance - postbalance, 9800)
+    })
+
+    it("revoke membership", async () => {
+      const sizeBefore = await collectionSize()
+
+      const txSig = await program.methods
+        .revokeMembership()
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          collectionMetadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: loyaltyCollectionPDA }),
+          collectionMasterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: loyaltyCollectionPDA }),
+          nftMint: nftMint,
+          metadataAccount: metaplex.nfts().pdas().metadata({ mint: nftMint }),
+          masterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: nftMint }),
+          tokenAccount: nftTokenAccount,
+          membership: membershipPDA,
+          sysvarInstructions: null,
+          systemProgram: null,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .rpc()
+
+      assert.strictEqual(await collectionSize(), sizeBefore - 1)
+
+      const membership = await program.account.membership.fetch(membershipPDA)
+      assert.isTrue(membership.revoked)
+
+      const tokenAccount = await spl.getAccount(connection, nftTokenAccount)
+      assert.isTrue(tokenAccount.isFrozen)
+
+      // checkout discount refused
+      try {
+        await memberTransaction(10000)
+        assert.fail("loyalty discount applied to revoked membership")
+      } catch (err) {
+        assert.include(
+          err.logs.join(),
+          "Error Code: RevokedMembership",
+          "expected RevokedMembership error"
+        )
+      }
+    })
+  })
+
+  describe("capped membership", () => {
+    const member = anchor.web3.Keypair.generate()
+    const rejectedMember = anchor.web3.Keypair.generate()
+    const memberMintPrice = 500
+
+    before(async () => {
+      // room for one more member
+      await program.methods
+        .updateMembershipConfig(
+          new anchor.BN((await collectionSize()) + 1),
+          new anchor.BN(memberMintPrice)
+        )
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    after(async () => {
+      await program.methods
+        .updateMembershipConfig(new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    it("mint member nft for mint price", async () => {
+      const memberUsdcTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        member.publicKey
+      )
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        memberUsdcTokenAccount,
+        wallet.payer,
+        memberMintPrice
+      )
+
+      const prebalance = Number(
+        (await connection.getTokenAccountBalance(paymentDestination)).value
+          .amount
+      )
+
+      await createTestLoyaltyNft(member, memberUsdcTokenAccount)
+
+      const postbalance = Number(
+        (await connection.getTokenAccountBalance(paymentDestination)).value
+          .amount
+      )
+      assert.strictEqual(postbalance - prebalance, memberMintPrice)
+    })
+
+    it("mint past max members fails", async () => {
+      const memberUsdcTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        rejectedMember.publicKey
+      )
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        memberUsdcTokenAccount,
+        wallet.payer,
+        memberMintPrice
+      )
+
+      try {
+        await createTestLoyaltyNft(rejectedMember, memberUsdcTokenAccount)
+        assert.fail("minted past max members")
+      } catch (err) {
+        assert.include(
+          err.logs.join(),
+          "Error Code: MaxMembersReached",
+          "expected MaxMembersReached error"
+        )
+      }
+    })
+  })
+
+  describe("time-limited membership", () => {
+    const member = anchor.web3.Keypair.generate()
+    // member minted before memberships expire, membership never expires
+    const lifetimeMember = anchor.web3.Keypair.generate()
+    const renewalPrice = 100
+    let nftMint: anchor.web3.PublicKey
+    let membershipPDA: anchor.web3.PublicKey
+    let memberUsdcTokenAccount: anchor.web3.PublicKey
+    let lifetimeNftMint: anchor.web3.PublicKey
+
+    before(async () => {
+      lifetimeNftMint = await createTestLoyaltyNft(lifetimeMember)
+
+      // memberships expire after 1 second
+      await program.methods
+        .updateMembershipTerm(new anchor.BN(1), new anchor.BN(renewalPrice))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+
+      nftMint = await createTestLoyaltyNft(member)
+      ;[membershipPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("MEMBERSHIP"), nftMint.toBuffer()],
+        program.programId
+      )
+
+      memberUsdcTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        member,
+        usdcPlaceholderMint,
+        member.publicKey
+      )
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        memberUsdcTokenAccount,
+        wallet.payer,
+        renewalPrice
+      )
+
+      // wait for membership to expire
+      await new Promise((resolve) => setTimeout(resolve, 3000))
+    })
+
+    after(async () => {
+      await program.methods
+        .updateMembershipTerm(new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    // refresh member nft uri, returns updated uri
+    async function refreshMembershipMetadata() {
+      const metadataPDA = metaplex.nfts().pdas().metadata({ mint: nftMint })
+      await program.methods
+        .refreshMembershipMetadata()
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          loyaltyLevels: null,
+          nftMint: nftMint,
+          metadataAccount: metadataPDA,
+          membership: membershipPDA,
+          sysvarInstructions: null,
+          authorizationRulesProgram: null,
+          authorizationRules: null,
+          systemProgram: null,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .rpc()
+
+      const accInfo = await connection.getAccountInfo(metadataPDA)
+      return Metadata.deserialize(accInfo.data, 0)[0].data.uri.replace(
+        /\0/g,
+        ""
+      )
+    }
+
+    it("expired membership refused loyalty discount", async () => {
+      const tx = await program.methods
+        .transaction(new anchor.BN(100))
+        .accounts({
+          customer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          paymentDestination: paymentDestination,
+          usdcMintPlaceholder: usdcPlaceholderMint,
+          customerUsdcTokenAccount: memberUsdcTokenAccount,
+          customerRewardTokenAccount: await spl.getAssociatedTokenAddress(
+            rewardPointsPDA,
+            member.publicKey
+          ),
+          coalitionPointsMint: null,
+          coalitionMember: null,
+          customerCoalitionTokenAccount: null,
+          customerNftTokenAccount: await spl.getAssociatedTokenAddress(
+            nftMint,
+            member.publicKey
+          ),
+          membership: membershipPDA,
+        })
+        .transaction()
+
+      try {
+        await anchor.web3.sendAndConfirmTransaction(connection, tx, [member])
+        assert.fail("loyalty discount applied to expired membership")
+      } catch (err) {
+        assert.include(
+          err.logs.join(),
+          "Error Code: MembershipExpired",
+          "expected MembershipExpired error"
+        )
+      }
+
+      // crank shows expired state on member nft
+      assert.strictEqual(
+        await refreshMembershipMetadata(),
+        memberTemplate.baseUri + "expired.json"
+      )
+    })
+
+    it("renew membership", async () => {
+      // renew for 1 hour so the member nft uri is restored
+      await program.methods
+        .updateMembershipTerm(new anchor.BN(3600), new anchor.BN(renewalPrice))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+
+      const txSig = await program.methods
+        .renewMembership()
+        .accounts({
+          customer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          paymentDestination: paymentDestination,
+          customerUsdcTokenAccount: memberUsdcTokenAccount,
+          nftMint: nftMint,
+          nftTokenAccount: await spl.getAssociatedTokenAddress(
+            nftMint,
+            member.publicKey
+          ),
+          membership: membershipPDA,
+        })
+        .signers([member])
+        .rpc()
+
+      // renewal price paid
+      const balance = Number(
+        (await connection.getTokenAccountBalance(memberUsdcTokenAccount)).value
+          .amount
+      )
+      assert.strictEqual(balance, 0)
+
+      const membership = await program.account.membership.fetch(membershipPDA)
+      assert.isAbove(membership.expiry.toNumber(), Date.now() / 1000)
+      assert.strictEqual(
+        await refreshMembershipMetadata(),
+        memberTemplate.baseUri + membership.memberNumber.toString() + ".json"
+      )
+    })
+
+    it("renew membership that never expires", async () => {
+      const [lifetimeMembershipPDA] =
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("MEMBERSHIP"), lifetimeNftMint.toBuffer()],
+          program.programId
+        )
+      const membership = await program.account.membership.fetch(
+        lifetimeMembershipPDA
+      )
+      assert.strictEqual(membership.expiry.toNumber(), 0)
+
+      const lifetimeUsdcTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        lifetimeMember.publicKey
+      )
+
+      try {
+        await program.methods
+          .renewMembership()
+          .accounts({
+            customer: lifetimeMember.publicKey,
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            paymentDestination: paymentDestination,
+            customerUsdcTokenAccount: lifetimeUsdcTokenAccount,
+            nftMint: lifetimeNftMint,
+            nftTokenAccount: await spl.getAssociatedTokenAddress(
+              lifetimeNftMint,
+              lifetimeMember.publicKey
+            ),
+            membership: lifetimeMembershipPDA,
+          })
+          .signers([lifetimeMember])
+          .rpc()
+        assert.fail("renewal turned lifetime membership into an expiring one")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "MembershipDoesNotExpire"
+        )
+      }
+    })
+
+    it("renew membership, expiry overflow", async () => {
+      // i64 max membership duration can't be added to the current expiry
+      await program.methods
+        .updateMembershipTerm(
+          new anchor.BN("9223372036854775807"),
+          new anchor.BN(0)
+        )
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+
+      try {
+        await program.methods
+          .renewMembership()
+          .accounts({
+            customer: member.publicKey,
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            paymentDestination: paymentDestination,
+            customerUsdcTokenAccount: memberUsdcTokenAccount,
+            nftMint: nftMint,
+            nftTokenAccount: await spl.getAssociatedTokenAddress(
+              nftMint,
+              member.publicKey
+            ),
+            membership: membershipPDA,
+          })
+          .signers([member])
+          .rpc()
+        assert.fail("membership expiry overflowed")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "MathOverflow")
+      }
+
+      await program.methods
+        .updateMembershipTerm(new anchor.BN(3600), new anchor.BN(renewalPrice))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+  })
+
+  describe("member nft perks", () => {
+    const member = anchor.web3.Keypair.generate()
+    const perkUses = 10
+    let nftMint: anchor.web3.PublicKey
+
+    before(async () => {
+      await program.methods
+        .updatePerks(new anchor.BN(perkUses))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+
+      nftMint = await createTestLoyaltyNft(member)
+    })
+
+    after(async () => {
+      await program.methods
+        .updatePerks(new anchor.BN(0))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    it("redeem perk", async () => {
+      const metadataPDA = metaplex.nfts().pdas().metadata({ mint: nftMint })
+
+      const txSig = await program.methods
+        .redeemPerk(new anchor.BN(2))
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customer: member.publicKey,
+          nftMint: nftMint,
+          tokenAccount: await spl.getAssociatedTokenAddress(
+            nftMint,
+            member.publicKey
+          ),
+          metadataAccount: metadataPDA,
+          useAuthorityRecord: useAuthorityRecordPDA(nftMint),
+          burner: burnerPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .rpc()
+
+      const accInfo = await connection.getAccountInfo(metadataPDA)
+      const metadata = Metadata.deserialize(accInfo.data, 0)
+      assert.strictEqual(Number(metadata[0].uses.total), perkUses)
+      assert.strictEqual(Number(metadata[0].uses.remaining), perkUses - 2)
+
+      const [membershipPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("MEMBERSHIP"), nftMint.toBuffer()],
+        program.programId
+      )
+      const membership = await program.account.membership.fetch(membershipPDA)
+      assert.strictEqual(membership.perksRedeemed.toNumber(), 2)
+    })
+  })
+
+  describe("print edition membership", () => {
+    const member = anchor.web3.Keypair.generate()
+    let nftMint: anchor.web3.PublicKey
+
+    before(async () => {
+      const tx = await program.methods
+        .createMemberMaster()
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          loyaltyCollectionMint: loyaltyCollectionPDA,
+          collectionMetadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: loyaltyCollectionPDA }),
+          memberMasterMint: memberMasterPDA,
+          metadataAccount: metaplex
+            .nfts()
+            .pdas()
+            .metadata({ mint: memberMasterPDA }),
+          masterEdition: metaplex
+            .nfts()
+            .pdas()
+            .masterEdition({ mint: memberMasterPDA }),
+          tokenAccount: await spl.getAssociatedTokenAddress(
+            memberMasterPDA,
+            loyaltyCollectionPDA,
+            true
+          ),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .transaction()
+
+      await anchor.web3.sendAndConfirmTransaction(
+        connection,
+        new anchor.web3.Transaction().add(
+          anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+            units: 400_000,
+          }),
+          tx
+        ),
+        [wallet.payer]
+      )
+
+      await program.methods
+        .updatePrintEditionMembership(true)
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    after(async () => {
+      await program.methods
+        .updatePrintEditionMembership(false)
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    it("mint print edition member nft", async () => {
+      const sizeBefore = await collectionSize()
+
+      nftMint = await createTestLoyaltyNft(member, null, true)
+
+      const [membershipPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("MEMBERSHIP"), nftMint.toBuffer()],
+        program.programId
+      )
+      const membership = await program.account.membership.fetch(membershipPDA)
+
+      const nft = await metaplex.nfts().findByMint({ mintAddress: nftMint })
+      assert.isFalse(nft.edition.isOriginal)
+      // @ts-ignore
+      const editionNumber = nft.edition.number.toNumber()
+      assert.strictEqual(editionNumber, membership.memberNumber.toNumber())
+      assert.isTrue(membership.printEdition)
+      assert.isTrue(nft.collection.verified)
+      assert.isTrue(nft.collection.address.equals(loyaltyCollectionPDA))
+      assert.strictEqual(await collectionSize(), sizeBefore + 1)
+    })
+
+    it("upgrade print edition member nft", async () => {
+      const memberRewardTokenAccount = await spl.getAssociatedTokenAddress(
+        rewardPointsPDA,
+        member.publicKey
+      )
+      await program.methods
+        .mintRewardPoints(new anchor.BN(10 ** rewardPointsDecimals))
+        .accounts({
+          authority: wallet.publicKey,
+          customer: member.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: memberRewardTokenAccount,
+        })
+        .rpc()
+
+      try {
+        await program.methods
+          .upgradeLoyaltyNft()
+          .accounts({
+            customer: member.publicKey,
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            customerRewardTokenAccount: memberRewardTokenAccount,
+            customerNftMint: nftMint,
+            customerNftTokenAccount: await spl.getAssociatedTokenAddress(
+              nftMint,
+              member.publicKey
+            ),
+            metadataAccount: metaplex.nfts().pdas().metadata({ mint: nftMint }),
+            sysvarInstructions: null,
+            authorizationRulesProgram: null,
+            authorizationRules: null,
+            systemProgram: null,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          })
+          .signers([member])
+          .rpc()
+        assert.fail("upgraded immutable print edition member nft")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "PrintEditionMetadataImmutable"
+        )
+      }
+    })
+
+    it("refresh print edition member metadata", async () => {
+      try {
+        await program.methods
+          .refreshMembershipMetadata()
+          .accounts({
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            loyaltyLevels: null,
+            nftMint: nftMint,
+            metadataAccount: metaplex.nfts().pdas().metadata({ mint: nftMint }),
+            membership: anchor.web3.PublicKey.findProgramAddressSync(
+              [Buffer.from("MEMBERSHIP"), nftMint.toBuffer()],
+              program.programId
+            )[0],
+            sysvarInstructions: null,
+            authorizationRulesProgram: null,
+            authorizationRules: null,
+            systemProgram: null,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          })
+          .rpc()
+        assert.fail("refreshed immutable print edition member metadata")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "PrintEditionMetadataImmutable"
+        )
+      }
+    })
+
+    it("reissue print edition member nft", async () => {
+      // burn print edition member nft
+      const nftTokenAccount = await spl.getAssociatedTokenAddress(
+        nftMint,
+        member.publicKey
+      )
+      await spl.burn(
+        connection,
+        wallet.payer,
+        nftTokenAccount,
+        nftMint,
+        member,
+        1
+      )
+
+      const version = 1
+      const versionBuffer = Buffer.alloc(4)
+      versionBuffer.writeUInt32LE(version)
+      const [reissuedNftMint] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("LOYALTY_NFT"),
+          merchantPDA.toBuffer(),
+          member.publicKey.toBuffer(),
+          versionBuffer,
+        ],
+        program.programId
+      )
+
+      try {
+        await program.methods
+          .reissueLoyaltyNft(version)
+          .accounts({
+            customer: member.publicKey,
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            collectionMetadataAccount: metaplex
+              .nfts()
+              .pdas()
+              .metadata({ mint: loyaltyCollectionPDA }),
+            collectionMasterEdition: metaplex
+              .nfts()
+              .pdas()
+              .masterEdition({ mint: loyaltyCollectionPDA }),
+            burnedNftMint: nftMint,
+            burnedMetadataAccount: metaplex
+              .nfts()
+              .pdas()
+              .metadata({ mint: nftMint }),
+            customerNftMint: reissuedNftMint,
+            metadataAccount: metaplex
+              .nfts()
+              .pdas()
+              .metadata({ mint: reissuedNftMint }),
+            masterEdition: metaplex
+              .nfts()
+              .pdas()
+              .masterEdition({ mint: reissuedNftMint }),
+            tokenAccount: await spl.getAssociatedTokenAddress(
+              reissuedNftMint,
+              member.publicKey
+            ),
+            useAuthorityRecord: useAuthorityRecordPDA(reissuedNftMint),
+            burner: burnerPDA,
+            loyaltyLevels: null,
+            tokenRecord: null,
+            sysvarInstructions: null,
+            authorizationRulesProgram: null,
+            authorizationRules: null,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          })
+          .signers([member])
+          .rpc()
+        assert.fail("reissued print edition member nft")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "PrintEditionReissueUnsupported"
+        )
+      }
+    })
+  })
+
+  describe("programmable member nft", () => {
+    const member = anchor.web3.Keypair.generate()
+    let nftMint: anchor.web3.PublicKey
+
+    before(async () => {
+      // no rule set, rule sets need the token auth rules client
+      await program.methods
+        .updateProgrammableLoyaltyNft(true, anchor.web3.PublicKey.default)
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    after(async () => {
+      await program.methods
+        .updateProgrammableLoyaltyNft(false, anchor.web3.PublicKey.default)
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    it("mint programmable member nft", async () => {
+      const sizeBefore = await collectionSize()
+
+      nftMint = await createTestLoyaltyNft(member, null, false, true)
+
+      const nft = await metaplex.nfts().findByMint({ mintAddress: nftMint })
+      assert.strictEqual(
+        nft.tokenStandard,
+        TokenStandard.ProgrammableNonFungible
+      )
+      assert.isTrue(nft.collection.verified)
+      assert.isTrue(nft.collection.address.equals(loyaltyCollectionPDA))
+      assert.isTrue(nft.creators[0].address.equals(merchantPDA))
+      assert.isTrue(nft.creators[0].verified)
+      assert.strictEqual(await collectionSize(), sizeBefore + 1)
+
+      // programmable nft token accounts are frozen by token metadata
+      const tokenAccount = await spl.getAccount(
+        connection,
+        await spl.getAssociatedTokenAddress(nftMint, member.publicKey)
+      )
+      assert.isTrue(tokenAccount.isFrozen)
+      assert.strictEqual(Number(tokenAccount.amount), 1)
+    })
+
+    it("upgrade programmable member nft", async () => {
+      const memberRewardTokenAccount = await spl.getAssociatedTokenAddress(
+        rewardPointsPDA,
+        member.publicKey
+      )
+      await program.methods
+        .mintRewardPoints(new anchor.BN(10 ** rewardPointsDecimals))
+        .accounts({
+          authority: wallet.publicKey,
+          customer: member.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: memberRewardTokenAccount,
+        })
+        .rpc()
+
+      const metadataPDA = metaplex.nfts().pdas().metadata({ mint: nftMint })
+      await program.methods
+        .upgradeLoyaltyNft()
+        .accounts({
+          customer: member.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: memberRewardTokenAccount,
+          customerNftMint: nftMint,
+          customerNftTokenAccount: await spl.getAssociatedTokenAddress(
+            nftMint,
+            member.publicKey
+          ),
+          metadataAccount: metadataPDA,
+          sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          authorizationRulesProgram: null,
+          authorizationRules: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([member])
+        .rpc()
+
+      const accInfo = await connection.getAccountInfo(metadataPDA)
+      const metadata = Metadata.deserialize(accInfo.data, 0)
+      assert.ok(metadata[0].data.uri.startsWith(testMetadata.uri + "?level=1"))
+      assert.isTrue(metadata[0].collection.verified)
+    })
+
+    it("update soulbound loyalty nft, programmable member nfts", async () => {
+      try {
+        await program.methods
+          .updateSoulboundLoyaltyNft(true)
+          .accounts({
+            authority: wallet.publicKey,
+          })
+          .rpc()
+        assert.fail("made programmable member nfts soulbound")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "ProgrammableLoyaltyNftIncompatible"
+        )
+      }
+    })
+  })
+
+  describe("withdraw collection nft", () => {
+    it("withdraw collection nft", async () => {
+      const destination = await spl.getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        loyaltyCollectionPDA,
+        wallet.publicKey
+      )
+
+      await program.methods
+        .withdrawCollectionNft()
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          loyaltyCollectionMint: loyaltyCollectionPDA,
+          collectionVault: collectionVaultPDA,
+          destination: destination.address,
+        })
+        .rpc()
+
+      const vault = await spl.getAccount(connection, collectionVaultPDA)
+      assert.equal(Number(vault.amount), 0)
+      const account = await spl.getAccount(connection, destination.address)
+      assert.equal(Number(account.amount), 1)
+    })
+
+    it("withdraw collection nft, non-authority signer", async () => {
+      const notAuthority = anchor.web3.Keypair.generate()
+      try {
+        await program.methods
+          .withdrawCollectionNft()
+          .accounts({
+            authority: notAuthority.publicKey,
+            merchant: merchantPDA,
+            loyaltyCollectionMint: loyaltyCollectionPDA,
+            collectionVault: collectionVaultPDA,
+            destination: collectionVaultPDA,
+          })
+          .signers([notAuthority])
+          .rpc()
+        assert.fail("expected withdraw by non-authority to fail")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "ConstraintSeeds")
+      }
+    })
+  })
+
+  describe("update collection metadata", () => {
+    const coCreator = anchor.web3.Keypair.generate()
+    const collectionMetadataPDA = metaplex
+      .nfts()
+      .pdas()
+      .metadata({ mint: loyaltyCollectionPDA })
+
+    it("update collection metadata with verified co-creator", async () => {
+      await program.methods
+        .updateCollectionMetadata(
+          "Grizzly Members",
+          "GRIZ",
+          "https://arweave.net/grizzly-members/collection.json",
+          0,
+          [
+            { address: wallet.publicKey, share: 60 },
+            { address: coCreator.publicKey, share: 40 },
+          ]
+        )
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          loyaltyCollectionMint: loyaltyCollectionPDA,
+          metadataAccount: collectionMetadataPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: coCreator.publicKey, isSigner: true, isWritable: false },
+        ])
+        .signers([coCreator])
+        .rpc()
+
+      const accInfo = await connection.getAccountInfo(collectionMetadataPDA)
+      const metadata = Metadata.deserialize(accInfo.data, 0)
+      assert.ok(metadata[0].data.name.startsWith("Grizzly Members"))
+      const creators = metadata[0].data.creators
+      assert.strictEqual(creators.length, 2)
+      assert.isTrue(creators[0].address.equals(wallet.publicKey))
+      assert.isTrue(creators[0].verified)
+      assert.strictEqual(creators[0].share, 60)
+      assert.isTrue(creators[1].address.equals(coCreator.publicKey))
+      assert.isTrue(creators[1].verified)
+      assert.strictEqual(creators[1].share, 40)
+    })
+
+    it("update collection metadata, shares not adding up to 100", async () => {
+      try {
+        await program.methods
+          .updateCollectionMetadata(
+            "Grizzly Members",
+            "GRIZ",
+            "https://arweave.net/grizzly-members/collection.json",
+            0,
+            [
+              { address: wallet.publicKey, share: 60 },
+              { address: coCreator.publicKey, share: 30 },
+            ]
+          )
+          .accounts({
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            loyaltyCollectionMint: loyaltyCollectionPDA,
+            metadataAccount: collectionMetadataPDA,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          })
+          .rpc()
+        assert.fail("updated collection with invalid creator shares")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "InvalidCreatorShares")
+      }
+    })
+
+    it("update collection metadata, merchant account as creator", async () => {
+      try {
+        await program.methods
+          .updateCollectionMetadata(
+            "Grizzly Members",
+            "GRIZ",
+            "https://arweave.net/grizzly-members/collection.json",
+            0,
+            [
+              { address: wallet.publicKey, share: 60 },
+              { address: merchantPDA, share: 40 },
+            ]
+          )
+          .accounts({
+            authority: wallet.publicKey,
+            merchant: merchantPDA,
+            loyaltyCollectionMint: loyaltyCollectionPDA,
+            metadataAccount: collectionMetadataPDA,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          })
+          .rpc()
+        assert.fail("updated collection with merchant account as creator")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "MerchantCollectionCreator"
+        )
+      }
+    })
+  })
+
+  describe("redeem voucher errors", () => {
+    const points = new anchor.BN(500)
+
+    const voucherExpiry = () =>
+      new anchor.BN(Math.floor(Date.now() / 1000) + 60 * 60)
+
+    const voucherMessage = (
+      voucherCustomer: anchor.web3.PublicKey,
+      points: anchor.BN,
+      nonce: anchor.BN,
+      expiry: anchor.BN
+    ) =>
+      Buffer.concat([
+        merchantPDA.toBuffer(),
+        voucherCustomer.toBuffer(),
+        points.toArrayLike(Buffer, "le", 8),
+        nonce.toArrayLike(Buffer, "le", 8),
+        expiry.toArrayLike(Buffer, "le", 8),
+      ])
+
+    const ed25519Instruction = (
+      signer: anchor.web3.Keypair,
+      message: Buffer
+    ) =>
+      anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: message,
+      })
+
+    const redeemInstruction = (
+      points: anchor.BN,
+      nonce: anchor.BN,
+      expiry: anchor.BN
+    ) => {
+      const [voucherNoncePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("VOUCHER"),
+          merchantPDA.toBuffer(),
+          nonce.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )
+
+      return program.methods
+        .redeemVoucher(points, nonce, expiry)
+        .accounts({
+          customer: customer.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+          voucherNonce: voucherNoncePDA,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .instruction()
+    }
+
+    // each test uses an unused nonce, so failures come from the voucher check
+    const expectVoucherError = async (
+      instructions: anchor.web3.TransactionInstruction[],
+      errorCode: string
+    ) => {
+      try {
+        await anchor.web3.sendAndConfirmTransaction(
+          connection,
+          new anchor.web3.Transaction().add(...instructions),
+          [customer]
+        )
+        assert.fail("voucher redeemed")
+      } catch (err) {
+        assert.include(
+          err.logs.join(),
+          `Error Code: ${errorCode}`,
+          `expected ${errorCode} error`
+        )
+      }
+    }
+
+    it("wrong voucher signer", async () => {
+      const nonce = new anchor.BN(101)
+      const expiry = voucherExpiry()
+      const message = voucherMessage(customer.publicKey, points, nonce, expiry)
+
+      await expectVoucherError(
+        [
+          ed25519Instruction(anchor.web3.Keypair.generate(), message),
+          await redeemInstruction(points, nonce, expiry),
+        ],
+        "VoucherSignerMismatch"
+      )
+    })
+
+    it("tampered voucher points", async () => {
+      const nonce = new anchor.BN(102)
+      const expiry = voucherExpiry()
+      const message = voucherMessage(customer.publicKey, points, nonce, expiry)
+
+      await expectVoucherError(
+        [
+          ed25519Instruction(voucherSigner, message),
+          await redeemInstruction(points.muln(10), nonce, expiry),
+        ],
+        "VoucherPayloadMismatch"
+      )
+    })
+
+    it("tampered voucher customer", async () => {
+      const nonce = new anchor.BN(103)
+      const expiry = voucherExpiry()
+      const message = voucherMessage(
+        anchor.web3.Keypair.generate().publicKey,
+        points,
+        nonce,
+        expiry
+      )
+
+      await expectVoucherError(
+        [
+          ed25519Instruction(voucherSigner, message),
+          await redeemInstruction(points, nonce, expiry),
+        ],
+        "VoucherPayloadMismatch"
+      )
+    })
+
+    it("tampered voucher expiry", async () => {
+      const nonce = new anchor.BN(104)
+      const expiry = voucherExpiry()
+      const message = voucherMessage(customer.publicKey, points, nonce, expiry)
+
+      await expectVoucherError(
+        [
+          ed25519Instruction(voucherSigner, message),
+          await redeemInstruction(points, nonce, expiry.addn(60 * 60)),
+        ],
+        "VoucherPayloadMismatch"
+      )
+    })
+
+    it("expired voucher", async () => {
+      const nonce = new anchor.BN(105)
+      const expiry = new anchor.BN(Math.floor(Date.now() / 1000) - 60 * 60)
+      const message = voucherMessage(customer.publicKey, points, nonce, expiry)
+
+      await expectVoucherError(
+        [
+          ed25519Instruction(voucherSigner, message),
+          await redeemInstruction(points, nonce, expiry),
+        ],
+        "VoucherExpired"
+      )
+    })
+
+    it("missing ed25519 instruction", async () => {
+      const nonce = new anchor.BN(106)
+      const expiry = voucherExpiry()
+
+      await expectVoucherError(
+        [await redeemInstruction(points, nonce, expiry)],
+        "InvalidVoucherSignatureInstruction"
+      )
+    })
+
+    it("ed25519 instruction not directly before redeem", async () => {
+      const nonce = new anchor.BN(107)
+      const expiry = voucherExpiry()
+      const message = voucherMessage(customer.publicKey, points, nonce, expiry)
+
+      await expectVoucherError(
+        [
+          ed25519Instruction(voucherSigner, message),
+          anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+            units: 200_000,
+          }),
+          await redeemInstruction(points, nonce, expiry),
+        ],
+        "InvalidVoucherSignatureInstruction"
+      )
+    })
+  })
+
+  describe("migrate merchant", () => {
+    it("migrate current merchant account, unchanged", async () => {
+      const preAccountInfo = await connection.getAccountInfo(merchantPDA)
+
+      await program.methods
+        .migrateMerchant()
+        .accounts({
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+        })
+        .rpc()
+
+      const postAccountInfo = await connection.getAccountInfo(merchantPDA)
+      assert.isTrue(postAccountInfo.data.equals(preAccountInfo.data))
+      assert.strictEqual(postAccountInfo.lamports, preAccountInfo.lamports)
+    })
+
+    it("migrate membership, membership already exists", async () => {
+      try {
+        await program.methods
+          .migrateMembership()
+          .accounts({
+            authority: wallet.publicKey,
+            customer: customer.publicKey,
+            nftMint: customerNftPDA,
+          })
+          .rpc()
+        assert.fail("membership created twice")
+      } catch (err) {
+        assert.include(
+          err.logs.join(),
+          "already in use",
+          "expected membership account already in use"
+        )
+      }
+    })
+  })
+
+  describe("points supply warnings", () => {
+    const supplyMerchant = anchor.web3.Keypair.generate()
+    const supplyCustomer = anchor.web3.Keypair.generate()
+    // initTestMerchant max points supply, 1% steps
+    const maxPointsSupply = 1_000_000 * 10 ** rewardPointsDecimals
+    const percent = maxPointsSupply / 100
+    const eventParser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    )
+
+    const [supplyMerchantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MERCHANT"), supplyMerchant.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [supplyPointsPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("REWARD_POINTS"), supplyMerchantPDA.toBuffer()],
+      program.programId
+    )
+
+    before(async () => {
+      await initTestMerchant(supplyMerchant)
+    })
+
+    // mint reward points, returns supply warning thresholds crossed
+    async function mintSupplyPoints(amount: number) {
+      const txSig = await program.methods
+        .mintRewardPoints(new anchor.BN(amount))
+        .accounts({
+          authority: supplyMerchant.publicKey,
+          customer: supplyCustomer.publicKey,
+          merchant: supplyMerchantPDA,
+          customerRewardTokenAccount: await spl.getAssociatedTokenAddress(
+            supplyPointsPDA,
+            supplyCustomer.publicKey
+          ),
+        })
+        .signers([supplyMerchant])
+        .rpc({ commitment: "confirmed" })
+
+      const tx = await connection.getTransaction(txSig, {
+        commitment: "confirmed",
+      })
+      return Array.from(eventParser.parseLogs(tx.meta.logMessages))
+        .filter((event) => event.name == "PointsSupplyWarning")
+        .map((event) => event.data.thresholdBasisPoints)
+    }
+
+    it("warn at 50%, 75% and 90% of max points supply", async () => {
+      assert.deepEqual(await mintSupplyPoints(percent * 50), [5000])
+      assert.deepEqual(await mintSupplyPoints(percent * 10), [])
+      assert.deepEqual(await mintSupplyPoints(percent * 15), [7500])
+      assert.deepEqual(await mintSupplyPoints(percent * 15), [9000])
+    })
+
+    it("mint past max points supply", async () => {
+      try {
+        await mintSupplyPoints(percent * 10 + 1)
+        assert.fail("minted past max points supply")
+      } catch (err) {
+        assert.strictEqual(err.error.errorCode.code, "MaxPointsSupplyExceeded")
+      }
+    })
+  })
+
+  describe("purchase streak", () => {
+    const streakMerchant = anchor.web3.Keypair.generate()
+    const streakCustomer = anchor.web3.Keypair.generate()
+
+    const [streakMerchantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("MERCHANT"), streakMerchant.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const [streakPointsPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("REWARD_POINTS"), streakMerchantPDA.toBuffer()],
+      program.programId
+    )
+
+    const [streakCustomerStatePDA] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("CUSTOMER"),
+          streakMerchantPDA.toBuffer(),
+          streakCustomer.publicKey.toBuffer(),
+        ],
+        program.programId
+      )
+
+    let streakPaymentDestination: anchor.web3.PublicKey
+    let streakCustomerUsdcTokenAccount: anchor.web3.PublicKey
+    let streakCustomerRewardTokenAccount: anchor.web3.PublicKey
+
+    before(async () => {
+      streakPaymentDestination = await initTestMerchant(streakMerchant)
+
+      const txSig = await connection.requestAirdrop(
+        streakCustomer.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      )
+
+      const { blockhash, lastValidBlockHeight } =
+        await connection.getLatestBlockhash()
+
+      await connection.confirmTransaction(
+        {
+          blockhash,
+          lastValidBlockHeight,
+          signature: txSig,
+        },
+        "confirmed"
+      )
+
+      streakCustomerUsdcTokenAccount = await spl.createAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        streakCustomer.publicKey
+      )
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        streakCustomerUsdcTokenAccount,
+        wallet.payer,
+        20000
+      )
+
+      streakCustomerRewardTokenAccount = await spl.getAssociatedTokenAddress(
+        streakPointsPDA,
+        streakCustomer.publicKey
+      )
+
+      // 1.5x reward points from the first qualifying purchase day,
+      // purchases of at least 5000 "usdc" count towards the streak
+      await program.methods
+        .updateStreakTiers(
+          [{ minStreakDays: 1, multiplierBasisPoints: 15000 }],
+          new anchor.BN(5000)
+        )
+        .accounts({
+          authority: streakMerchant.publicKey,
+          merchant: streakMerchantPDA,
+        })
+        .signers([streakMerchant])
+        .rpc()
+    })
+
+    // checkout with streak merchant, returns reward points minted
+    async function streakTransaction(amount: number) {
+      const prebalance = await rewardPointsBalance()
+
+      const tx = await program.methods
+        .transaction(new anchor.BN(amount))
+        .accounts({
+          customer: streakCustomer.publicKey,
+          authority: streakMerchant.publicKey,
+          merchant: streakMerchantPDA,
+          paymentDestination: streakPaymentDestination,
+          usdcMintPlaceholder: usdcPlaceholderMint,
+          customerUsdcTokenAccount: streakCustomerUsdcTokenAccount,
+          customerRewardTokenAccount: streakCustomerRewardTokenAccount,
+          customerState: streakCustomerStatePDA,
+          coalitionPointsMint: null,
+          coalitionMember: null,
+          customerCoalitionTokenAccount: null,
+          customerNftTokenAccount: null,
+          membership: null,
+        })
+        .transaction()
+
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [
+        streakCustomer,
+      ])
+
+      return (await rewardPointsBalance()) - prebalance
+    }
+
+    async function rewardPointsBalance() {
+      const accountInfo = await connection.getAccountInfo(
+        streakCustomerRewardTokenAccount
+      )
+      if (accountInfo == null) {
+        return 0
+      }
+      return Number(
+        (
+          await connection.getTokenAccountBalance(
+            streakCustomerRewardTokenAccount
+          )
+        ).value.amount
+      )
+    }
+
+    it("zero amount checkout doesn't start a streak", async () => {
+      assert.strictEqual(await streakTransaction(0), 0)
+
+      const customerState = await program.account.customerState.fetch(
+        streakCustomerStatePDA
+      )
+      assert.equal(customerState.currentStreak, 0)
+      assert.equal(customerState.longestStreak, 0)
+    })
+
+    it("checkout below min streak purchase, no streak or bonus", async () => {
+      // 1% of 1000 "usdc" is 10 reward points, no streak bonus
+      assert.strictEqual(
+        await streakTransaction(1000),
+        10 * 10 ** rewardPointsDecimals
+      )
+
+      const customerState = await program.account.customerState.fetch(
+        streakCustomerStatePDA
+      )
+      assert.equal(customerState.currentStreak, 0)
+    })
+
+    it("qualifying checkout starts streak with bonus", async () => {
+      // 1% of 10000 "usdc" is 100 reward points, 1.5x streak multiplier
+      assert.strictEqual(
+        await streakTransaction(10000),
+        150 * 10 ** rewardPointsDecimals
+      )
+
+      const customerState = await program.account.customerState.fetch(
+        streakCustomerStatePDA
+      )
+      assert.equal(customerState.currentStreak, 1)
+      assert.equal(customerState.longestStreak, 1)
+    })
+
+    it("zero amount checkout earns no streak bonus", async () => {
+      const preState = await program.account.customerState.fetch(
+        streakCustomerStatePDA
+      )
+
+      assert.strictEqual(await streakTransaction(0), 0)
+
+      const postState = await program.account.customerState.fetch(
+        streakCustomerStatePDA
+      )
+      assert.equal(postState.currentStreak, preState.currentStreak)
+      assert.isTrue(postState.lastQualifyingDay.eq(preState.lastQualifyingDay))
+    })
+  })
+
+  describe("points vesting per checkout", () => {
+    const pointsVestingPeriod = 6
+
+    before(async () => {
+      await program.methods
+        .updatePointsVesting(new anchor.BN(pointsVestingPeriod))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+
+      // mint "usdc" tokens to customer
+      await spl.mintTo(
+        connection,
+        wallet.payer,
+        usdcPlaceholderMint,
+        customerUsdcTokenAccount,
+        wallet.payer,
+        200
+      )
+    })
+
+    after(async () => {
+      await program.methods
+        .updatePointsVesting(new anchor.BN(0))
+        .accounts({
+          authority: wallet.publicKey,
+        })
+        .rpc()
+    })
+
+    async function pendingCheckout(amount: number) {
+      const tx = await program.methods
+        .transaction(new anchor.BN(amount))
+        .accounts({
+          customer: customer.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          paymentDestination: paymentDestination,
+          usdcMintPlaceholder: usdcPlaceholderMint,
+          customerUsdcTokenAccount: customerUsdcTokenAccount,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+          customerState: customerStatePDA,
+          coalitionPointsMint: null,
+          coalitionMember: null,
+          customerCoalitionTokenAccount: null,
+          customerNftTokenAccount: null,
+          membership: null,
+        })
+        .transaction()
+
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [customer])
+    }
+
+    it("each checkout vests on its own", async () => {
+      // 2% of 100 "usdc" is 2 reward points per checkout
+      const checkoutPoints = 2 * 10 ** rewardPointsDecimals
+
+      await pendingCheckout(100)
+      await new Promise((resolve) => setTimeout(resolve, 3000))
+      await pendingCheckout(100)
+
+      // pending reward points reserved against max points supply
+      let merchantAccount = await program.account.merchantState.fetch(
+        merchantPDA
+      )
+      assert.strictEqual(
+        merchantAccount.pendingPointsSupply.toNumber(),
+        2 * checkoutPoints
+      )
+
+      // wait for the first checkout to vest
+      let customerState = await program.account.customerState.fetch(
+        customerStatePDA
+      )
+      const vestTimestamps = customerState.pendingBuckets
+        .filter((bucket) => bucket.amount.toNumber() > 0)
+        .map((bucket) => bucket.vestTimestamp.toNumber())
+      assert.strictEqual(vestTimestamps.length, 2)
+      const firstVest = Math.min(...vestTimestamps)
+      await new Promise((resolve) =>
+        setTimeout(resolve, (firstVest + 1) * 1000 - Date.now())
+      )
+
+      const prebalance = Number(
+        (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+          .value.amount
+      )
+
+      await program.methods
+        .vestPoints()
+        .accounts({
+          payer: wallet.publicKey,
+          customer: customer.publicKey,
+          authority: wallet.publicKey,
+          merchant: merchantPDA,
+          customerRewardTokenAccount: customerRewardTokenAccount,
+          customerState: customerStatePDA,
+        })
+        .rpc()
+
+      // only the first checkout's reward points minted
+      const postbalance = Number(
+        (await connection.getTokenAccountBalance(customerRewardTokenAccount))
+          .value.amount
+      )
+      assert.strictEqual(postbalance - prebalance, checkoutPoints)
+
+      customerState = await program.account.customerState.fetch(
+        customerStatePDA
+      )
+      assert.strictEqual(customerState.pendingPoints.toNumber(), checkoutPoints)
+
+      merchantAccount = await program.account.merchantState.fetch(merchantPDA)
+      assert.strictEqual(
+        merchantAccount.pendingPointsSupply.toNumber(),
+        checkoutPoints
+      )
+    })
+
+    it("cancel pending points for refunded checkout", async () => {
+      const points = new anchor.BN(2 * 10 ** rewardPointsDecimals)
+      const txSig = await program.methods
+        .cancelPendingPoints(points)
+        .accounts({
+          authority: wallet.publicKey,
+          customer: customer.publicKey,
+          merchant: merchantPDA,
+          customerState: customerStatePDA,
+        })
+        .rpc()
+
+      const customerState = await program.account.customerState.fetch(
+        customerStatePDA
+      )
+      assert.strictEqual(customerState.pendingPoints.toNumber(), 0)
+
+      const merchantAccount = await program.account.merchantState.fetch(
+        merchantPDA
+      )
+      assert.strictEqual(merchantAccount.pendingPointsSupply.toNumber(), 0)
+    })
+
+    it("cancel more than pending points", async () => {
+      try {
+        await program.methods
+          .cancelPendingPoints(new anchor.BN(1))
+          .accounts({
+            authority: wallet.publicKey,
+            customer: customer.publicKey,
+            merchant: merchantPDA,
+            customerState: customerStatePDA,
+          })
+          .rpc()
+        assert.fail("cancelled more than pending points")
+      } catch (err) {
+        assert.strictEqual(
+          err.error.errorCode.code,
+          "InsufficientPendingPoints"
+        )
+      }
+    })
+  })
 })